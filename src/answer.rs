@@ -0,0 +1,63 @@
+use num::BigInt;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(BigInt),
+    Text(String),
+}
+
+macro_rules! from_integer {
+    ($($type:ty),+) => {
+        $(
+            impl From<$type> for Answer {
+                fn from(value: $type) -> Self {
+                    Self::Integer(BigInt::from(value))
+                }
+            }
+        )+
+    };
+}
+
+from_integer!(usize, u32, u64, u128, isize, i32, i64, i128);
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(42_usize), Answer::from(42_i32));
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from(-7_i64).to_string(), "-7");
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+        assert_ne!(Answer::from(1_usize), Answer::from("1"));
+    }
+}
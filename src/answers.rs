@@ -0,0 +1,157 @@
+use crate::runner::{Outcome, Run};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    answers: HashMap<(u8, u8), String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail { expected: String },
+    Unrecorded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnswersError(String);
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid answers file: {}", self.0)
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected } => write!(f, "fail, expected {expected}"),
+            Self::Unrecorded => write!(f, "no recorded answer"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn read(path: &Path) -> Result<Self, AnswersError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| AnswersError(format!("could not read {}: {error}", path.display())))?;
+
+        Self::parse(&contents)
+    }
+
+    // The file has a table per day with a key per part, e.g. `[day05]` and `part2 = 46`.
+    // Answers too big for a TOML integer can be written as strings.
+    pub fn parse(contents: &str) -> Result<Self, AnswersError> {
+        let table = contents
+            .parse::<Table>()
+            .map_err(|error| AnswersError(error.to_string()))?;
+        let mut answers = Self::default();
+
+        for (day_key, parts) in table {
+            let day = parse_key(&day_key, "day")?;
+            let Value::Table(parts) = parts else {
+                return Err(AnswersError(format!("[{day_key}] should be a table")));
+            };
+
+            for (part_key, answer) in parts {
+                let part = parse_key(&part_key, "part")?;
+                let answer = match answer {
+                    Value::Integer(answer) => answer.to_string(),
+                    Value::String(answer) => answer,
+                    _ => {
+                        return Err(AnswersError(format!(
+                            "{day_key}.{part_key} should be an integer or a string"
+                        )))
+                    }
+                };
+
+                answers.answers.insert((day, part), answer);
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&String> {
+        self.answers.get(&(day, part))
+    }
+
+    pub fn check(&self, run: &Run) -> Check {
+        let Some(expected) = self.get(run.day, run.part) else {
+            return Check::Unrecorded;
+        };
+
+        match &run.outcome {
+            Outcome::Solved(answer) if answer.to_string() == *expected => Check::Pass,
+            _ => Check::Fail {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+}
+
+fn parse_key(key: &str, prefix: &str) -> Result<u8, AnswersError> {
+    key.strip_prefix(prefix)
+        .and_then(|number| number.parse::<u8>().ok())
+        .ok_or_else(|| AnswersError(format!("{key} should look like {prefix}1")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> &'static str {
+        r#"[day01]
+           part1 = 142
+           part2 = "281"
+
+           [day19]
+           part2 = "167409079868000""#
+    }
+
+    fn get_run(day: u8, part: u8, answer: usize) -> Run {
+        Run {
+            day,
+            part,
+            outcome: Outcome::Solved(answer.into()),
+            elapsed: None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(get_fixture()).expect("should parse");
+
+        assert_eq!(answers.get(1, 1), Some(&"142".to_string()));
+        assert_eq!(answers.get(1, 2), Some(&"281".to_string()));
+        assert_eq!(answers.get(19, 2), Some(&"167409079868000".to_string()));
+        assert_eq!(answers.get(2, 1), None);
+        assert_eq!(
+            Answers::parse("[first]\npart1 = 1")
+                .unwrap_err()
+                .to_string(),
+            "Invalid answers file: first should look like day1"
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(get_fixture()).expect("should parse");
+
+        assert_eq!(answers.check(&get_run(1, 1, 142)), Check::Pass);
+        assert_eq!(
+            answers.check(&get_run(1, 2, 280)),
+            Check::Fail {
+                expected: "281".to_string()
+            }
+        );
+        assert_eq!(answers.check(&get_run(3, 1, 0)), Check::Unrecorded);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::registry::Solver;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bench {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub durations: Vec<Duration>,
+}
+
+// The first run warms caches and the rayon thread pool, so it is thrown away.
+pub fn bench(solver: &Solver, input: &[String], runs: usize) -> Result<Bench, SolveError> {
+    let mut durations = vec![];
+    let mut answer = (solver.solve)(input.to_vec())?;
+
+    for _ in 0..runs {
+        let input = input.to_vec();
+        let start = Instant::now();
+
+        answer = (solver.solve)(input)?;
+        durations.push(start.elapsed());
+    }
+
+    durations.sort();
+
+    Ok(Bench {
+        day: solver.day,
+        part: solver.part,
+        answer,
+        durations,
+    })
+}
+
+impl Bench {
+    pub fn get_min(&self) -> Duration {
+        *self.durations.first().expect("should have a duration")
+    }
+
+    pub fn get_max(&self) -> Duration {
+        *self.durations.last().expect("should have a duration")
+    }
+
+    pub fn get_median(&self) -> Duration {
+        let middle = self.durations.len() / 2;
+
+        if self.durations.len().is_multiple_of(2) {
+            (self.durations[middle - 1] + self.durations[middle]) / 2
+        } else {
+            self.durations[middle]
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"part\":{},\"answer\":\"{}\",\"runs\":{},\"min_ns\":{},\"median_ns\":{},\"max_ns\":{}}}",
+            self.day,
+            self.part,
+            escape_json(&self.answer.to_string()),
+            self.durations.len(),
+            self.get_min().as_nanos(),
+            self.get_median().as_nanos(),
+            self.get_max().as_nanos(),
+        )
+    }
+}
+
+// Text answers can hold anything, so quotes, backslashes and control characters are escaped to
+// keep the line valid JSON.
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl fmt::Display for Bench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} part {}: {}\nruns: {}, min: {:.2?}, median: {:.2?}, max: {:.2?}",
+            self.day,
+            self.part,
+            self.answer,
+            self.durations.len(),
+            self.get_min(),
+            self.get_median(),
+            self.get_max(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Bench {
+        Bench {
+            day: 5,
+            part: 2,
+            answer: 46.into(),
+            durations: [1, 2, 4, 8].map(Duration::from_millis).to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_bench() {
+        let solver = Solver {
+            day: 1,
+            part: 2,
+            solve: |input| Ok(input.len().into()),
+            render: None,
+        };
+        let bench = bench(&solver, &["a".to_string()], 3).expect("should solve");
+
+        assert_eq!(bench.answer, 1.into());
+        assert_eq!(bench.durations.len(), 3);
+        assert!(bench.get_min() <= bench.get_median());
+        assert!(bench.get_median() <= bench.get_max());
+    }
+
+    #[test]
+    fn test_get_median() {
+        let mut bench = get_fixture();
+
+        assert_eq!(bench.get_median(), Duration::from_millis(3));
+
+        bench.durations.pop();
+
+        assert_eq!(bench.get_median(), Duration::from_millis(2));
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            get_fixture().to_json(),
+            "{\"day\":5,\"part\":2,\"answer\":\"46\",\"runs\":4,\"min_ns\":1000000,\"median_ns\":3000000,\"max_ns\":8000000}"
+        );
+
+        let bench = Bench {
+            answer: "say \"hi\"\\\n".into(),
+            ..get_fixture()
+        };
+
+        assert_eq!(
+            bench.to_json(),
+            "{\"day\":5,\"part\":2,\"answer\":\"say \\\"hi\\\"\\\\\\n\",\"runs\":4,\"min_ns\":1000000,\"median_ns\":3000000,\"max_ns\":8000000}"
+        );
+    }
+}
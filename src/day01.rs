@@ -1,39 +1,39 @@
-pub fn part1(input: Vec<String>) -> usize {
-    let collection = input.iter().map(|line| parse_numbers(line.as_str()));
-    let result = &collection.sum();
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
 
-    *result
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let mut result = 0;
+
+    for (row, line) in input.iter().enumerate() {
+        result += parse_numbers(line, row)?;
+    }
+
+    Ok(result.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let collection = input.iter().map(|line| parse_numbers_plus(line.as_str()));
-    let result = &collection.sum::<usize>();
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let mut result = 0;
 
-    *result
+    for (row, line) in input.iter().enumerate() {
+        result += parse_numbers_plus(line, row)?;
+    }
+
+    Ok(result.into())
 }
 
-fn parse_numbers(line: &str) -> usize {
+fn parse_numbers(line: &str, row: usize) -> Result<usize, ParseError> {
     let filtered = line
         .chars()
-        .filter(|c| c.is_ascii_digit())
+        .filter_map(|c| c.to_digit(10))
         .collect::<Vec<_>>();
-    let first = filtered
-        .first()
-        .expect("should have a first")
-        .to_string()
-        .parse::<usize>()
-        .expect("should parse a number here");
-    let last = filtered
-        .last()
-        .expect("should have a last")
-        .to_string()
-        .parse::<usize>()
-        .expect("should parse a number here");
-
-    (first * 10) + last
+    let (Some(first), Some(last)) = (filtered.first(), filtered.last()) else {
+        return Err(ParseError::new(row, 0, "a digit"));
+    };
+
+    Ok((*first as usize * 10) + *last as usize)
 }
 
-fn parse_numbers_plus(line: &str) -> usize {
+fn parse_numbers_plus(line: &str, row: usize) -> Result<usize, ParseError> {
     let line = line
         .replace("zero", "zero0zero")
         .replace("one", "one1one")
@@ -46,7 +46,7 @@ fn parse_numbers_plus(line: &str) -> usize {
         .replace("eight", "eight8eight")
         .replace("nine", "nine9nine");
 
-    parse_numbers(&line)
+    parse_numbers(&line, row)
 }
 
 #[cfg(test)]
@@ -80,13 +80,13 @@ mod tests {
     fn test_part1() {
         let fixture1 = get_fixture1();
 
-        assert_eq!(part1(fixture1), 142);
+        assert_eq!(part1(fixture1), Ok(142.into()));
     }
 
     #[test]
     fn test_part2() {
         let fixture2 = get_fixture2();
 
-        assert_eq!(part2(fixture2), 281);
+        assert_eq!(part2(fixture2), Ok(281.into()));
     }
 }
@@ -1,3 +1,5 @@
+use crate::answer::Answer;
+use crate::error::{parse_capture, parse_number, ParseError, SolveError};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -14,12 +16,16 @@ struct Game {
     highs: Colors,
 }
 
-pub fn part1(input: Vec<String>) -> usize {
-    let maxes = parse_colors("12 red cubes, 13 green cubes, and 14 blue cubes".to_string());
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let maxes = Colors {
+        red: 12,
+        green: 13,
+        blue: 14,
+    };
     let mut collection = Vec::new();
 
-    for line in input {
-        let game = parse_game(line);
+    for (row, line) in input.into_iter().enumerate() {
+        let game = parse_game(line, row)?;
 
         if game.highs.red <= maxes.red
             && game.highs.green <= maxes.green
@@ -29,26 +35,26 @@ pub fn part1(input: Vec<String>) -> usize {
         }
     }
 
-    let result = &collection.iter().sum();
+    let result = collection.iter().sum::<usize>();
 
-    *result
+    Ok(result.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut collection = Vec::new();
 
-    for line in input {
-        let game = parse_game(line);
+    for (row, line) in input.into_iter().enumerate() {
+        let game = parse_game(line, row)?;
 
         collection.push(game.highs.red * game.highs.green * game.highs.blue);
     }
 
-    let result = &collection.iter().sum();
+    let result = collection.iter().sum::<usize>();
 
-    *result
+    Ok(result.into())
 }
 
-fn parse_colors(line: String) -> Colors {
+fn parse_colors(line: &str, row: usize, offset: usize) -> Result<Colors, ParseError> {
     static COLOR_REGEX: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"(\d+) (red|blue|green)").expect("should be a valid regex"));
 
@@ -58,10 +64,14 @@ fn parse_colors(line: String) -> Colors {
         blue: 0,
     };
 
-    for (_, [count, color]) in COLOR_REGEX.captures_iter(&line).map(|c| c.extract()) {
-        let count = count.parse::<usize>().expect("should be a valid usize");
+    for captures in COLOR_REGEX.captures_iter(line) {
+        let count = parse_number::<usize>(
+            &captures[1],
+            row,
+            offset + captures.get(1).expect("should have a count").start(),
+        )?;
 
-        match color {
+        match &captures[2] {
             "red" => {
                 colors.red = if count > colors.red {
                     count
@@ -83,29 +93,28 @@ fn parse_colors(line: String) -> Colors {
                     colors.blue
                 }
             }
-            _ => panic!("should be a valid color"),
+            _ => unreachable!("should be a valid color"),
         }
     }
 
-    colors
+    Ok(colors)
 }
 
-fn parse_game(line: String) -> Game {
+fn parse_game(line: String, row: usize) -> Result<Game, ParseError> {
     static GAME_REGEX: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r"Game (?P<id>\d+): (?P<colors>.*)").expect("should be a valid regex")
     });
 
     let captures = GAME_REGEX
         .captures(line.as_str())
-        .expect("should be able to capture");
-    let id = &captures["id"]
-        .parse::<usize>()
-        .expect("should be a valid usize");
-
-    Game {
-        id: *id,
-        highs: parse_colors(captures["colors"].to_string()),
-    }
+        .ok_or_else(|| ParseError::new(row, 0, "Game <id>: <cubes>"))?;
+    let id = parse_capture::<usize>(&captures, "id", row)?;
+    let colors = captures.name("colors").expect("should have colors");
+
+    Ok(Game {
+        id,
+        highs: parse_colors(colors.as_str(), row, colors.start())?,
+    })
 }
 
 #[cfg(test)]
@@ -127,13 +136,29 @@ mod tests {
     fn test_part1() {
         let input = get_fixture();
 
-        assert_eq!(part1(input), 8);
+        assert_eq!(part1(input), Ok(8.into()));
     }
 
     #[test]
     fn test_part2() {
         let input = get_fixture();
 
-        assert_eq!(part2(input), 2286);
+        assert_eq!(part2(input), Ok(2286.into()));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let mut input = get_fixture();
+
+        input[2] = "Game three: 1 red".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(2, 0, "Game <id>: <cubes>").into())
+        );
+
+        input[2] = "Game 3: 1 red, 99999999999999999999 blue".to_string();
+
+        assert_eq!(part2(input), Err(ParseError::new(2, 15, "a number").into()));
     }
 }
@@ -1,6 +1,8 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::grid::{Coords, Grid};
 use std::collections::{HashMap, HashSet};
 
-type Coords = (usize, usize);
 type CoordsSet = HashSet<Coords>;
 type SymbolMap = HashMap<Coords, char>;
 type NumberList = Vec<Number>;
@@ -12,9 +14,9 @@ struct Number {
     field: CoordsSet,
 }
 
-pub fn part1(input: Vec<String>) -> usize {
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut collection = Vec::new();
-    let (symbols, numbers) = parse_input(input);
+    let (symbols, numbers) = parse_input(input)?;
     let symbol_locations = symbols.keys().cloned().collect::<_>();
 
     for number in numbers {
@@ -23,14 +25,14 @@ pub fn part1(input: Vec<String>) -> usize {
         }
     }
 
-    let result = &collection.iter().sum();
+    let result = collection.iter().sum::<usize>();
 
-    *result
+    Ok(result.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut collection = Vec::new();
-    let (symbols, numbers) = parse_input(input);
+    let (symbols, numbers) = parse_input(input)?;
 
     for (location, symbol) in symbols {
         if symbol == '*' {
@@ -48,60 +50,43 @@ pub fn part2(input: Vec<String>) -> usize {
         }
     }
 
-    let result = &collection.iter().sum();
+    let result = collection.iter().sum::<usize>();
 
-    *result
+    Ok(result.into())
 }
 
-fn parse_input(input: Vec<String>) -> Output {
+fn parse_input(input: Vec<String>) -> Result<Output, ParseError> {
+    let grid = Grid::parse(&input, "a digit, symbol or .", Some)?;
     let mut symbols = SymbolMap::new();
     let mut numbers = NumberList::new();
-    let mut current = Number::default();
-
-    for (row, line) in (0..).zip(input) {
-        for (column, char) in (0..).zip(line.chars()) {
-            if char.is_ascii_digit() {
-                current.value = (current.value * 10)
-                    + char
-                        .to_string()
-                        .parse::<usize>()
-                        .expect("should be a number");
-
-                if row > 0 && column > 0 {
-                    current.field.insert((row - 1, column - 1));
-                }
-
-                if row > 0 {
-                    current.field.insert((row - 1, column));
-                    current.field.insert((row - 1, column + 1));
-                }
 
-                if column > 0 {
-                    current.field.insert((row, column - 1));
-                }
+    for (row, cells) in grid.get_rows().enumerate() {
+        let mut current = Number::default();
 
-                current.field.insert((row, column + 1));
+        for (column, cell) in cells.iter().enumerate() {
+            if let Some(digit) = cell.to_digit(10) {
+                current.value = current.value * 10 + digit as usize;
+                current.field.extend(grid.get_all_neighbors((row, column)));
 
-                if column > 0 {
-                    current.field.insert((row + 1, column - 1));
-                }
+                continue;
+            }
 
-                current.field.insert((row + 1, column));
-                current.field.insert((row + 1, column + 1));
-            } else {
-                if char != '.' {
-                    symbols.insert((row, column), char);
-                }
+            if *cell != '.' {
+                symbols.insert((row, column), *cell);
+            }
 
-                if current.value > 0 {
-                    numbers.push(current);
-                    current = Number::default();
-                }
+            if current.value > 0 {
+                numbers.push(current);
+                current = Number::default();
             }
         }
+
+        if current.value > 0 {
+            numbers.push(current);
+        }
     }
 
-    (symbols, numbers)
+    Ok((symbols, numbers))
 }
 
 #[cfg(test)]
@@ -128,13 +113,13 @@ mod tests {
     fn test_part1() {
         let fixture = get_fixture();
 
-        assert_eq!(part1(fixture), 4361);
+        assert_eq!(part1(fixture), Ok(4361.into()));
     }
 
     #[test]
     fn test_part2() {
         let fixture = get_fixture();
 
-        assert_eq!(part2(fixture), 467835);
+        assert_eq!(part2(fixture), Ok(467835.into()));
     }
 }
@@ -1,3 +1,5 @@
+use crate::answer::Answer;
+use crate::error::{parse_capture, parse_numbers, ParseError, SolveError};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashSet;
@@ -11,11 +13,11 @@ struct Game {
     actual: NumberSet,
 }
 
-pub fn part1(input: Vec<String>) -> usize {
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut collection = Vec::new();
 
-    for line in input {
-        let game = parse_game(line);
+    for (row, line) in input.into_iter().enumerate() {
+        let game = parse_game(line, row)?;
         let win_count = game.winning.intersection(&game.actual).count();
         let points = if win_count > 0 {
             1 << (win_count - 1)
@@ -26,16 +28,16 @@ pub fn part1(input: Vec<String>) -> usize {
         collection.push(points);
     }
 
-    let result = &collection.iter().sum();
+    let result = collection.iter().sum::<usize>();
 
-    *result
+    Ok(result.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut collection = vec![1; input.len()];
 
     for (row, line) in (0..).zip(input) {
-        let game = parse_game(line);
+        let game = parse_game(line, row)?;
         let win_count = game.winning.intersection(&game.actual).count();
 
         for i in (row + 1)..=(win_count + row) {
@@ -45,37 +47,34 @@ pub fn part2(input: Vec<String>) -> usize {
         }
     }
 
-    let result = &collection.iter().sum();
+    let result = collection.iter().sum::<usize>();
 
-    *result
+    Ok(result.into())
 }
 
-fn parse_game(line: String) -> Game {
-    let line = line.replace("  ", " ");
-
+fn parse_game(line: String, row: usize) -> Result<Game, ParseError> {
     static GAME_REGEX: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r"Card\s+(?P<id>\d+): (?P<winning>.*?) \| (?P<actual>.*)")
+        Regex::new(r"Card\s+(?P<id>\d+):(?P<winning>.*?)\|(?P<actual>.*)")
             .expect("should be a valid regex")
     });
 
     let captures = GAME_REGEX
         .captures(line.as_str())
-        .expect("should be able to capture");
-    let id = &captures["id"]
-        .parse::<usize>()
-        .expect("should be a valid usize");
-
-    Game {
-        id: *id,
-        winning: captures["winning"]
-            .split(' ')
-            .map(|c| c.parse::<usize>().expect("should be a valid usize"))
+        .ok_or_else(|| ParseError::new(row, 0, "Card <id>: <numbers> | <numbers>"))?;
+    let winning = captures
+        .name("winning")
+        .expect("should have winning numbers");
+    let actual = captures.name("actual").expect("should have actual numbers");
+
+    Ok(Game {
+        id: parse_capture(&captures, "id", row)?,
+        winning: parse_numbers::<usize>(winning.as_str(), ' ', row, winning.start())?
+            .into_iter()
             .collect(),
-        actual: captures["actual"]
-            .split(' ')
-            .map(|c| c.parse::<usize>().expect("should be a valid usize"))
+        actual: parse_numbers::<usize>(actual.as_str(), ' ', row, actual.start())?
+            .into_iter()
             .collect(),
-    }
+    })
 }
 
 #[cfg(test)]
@@ -96,11 +95,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 13);
+        assert_eq!(part1(get_fixture()), Ok(13.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 30);
+        assert_eq!(part2(get_fixture()), Ok(30.into()));
     }
 }
@@ -1,192 +1,270 @@
+use crate::answer::Answer;
+use crate::error::{parse_numbers, ParseError, SolveError};
+use crate::ranges::RangeMap;
+use crate::registry::RenderOptions;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use rayon::prelude::*;
 use regex::Regex;
-
-type MapList = Vec<Map>;
+use std::ops::Range;
 
 #[derive(Debug, Clone)]
-struct Map {
-    a: isize,
-    b: isize,
-    len: isize,
+struct Stage {
+    source: String,
+    destination: String,
+    map: RangeMap<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub category: String,
+    pub value: u64,
 }
 
-pub fn part1(input: Vec<String>) -> usize {
-    let seeds = parse_seeds1(input[0].to_owned());
-    let maps = parse_maps(input[1..].to_owned());
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let seeds = parse_seeds1(&input)?;
+    let stages = parse_maps(&input)?;
 
-    get_lowest1(seeds, maps)
+    Ok(get_lowest1(seeds, &stages)?.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let seeds = parse_seeds2(input[0].to_owned());
-    let mut maps = parse_maps(input[1..].to_owned());
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let seeds = parse_seeds2(&input)?;
+    let stages = parse_maps(&input)?;
 
-    maps.reverse();
+    Ok(get_lowest2(seeds, &stages)?.into())
+}
+
+pub fn render1(input: Vec<String>, _options: &RenderOptions) -> Result<String, SolveError> {
+    let seeds = parse_seeds1(&input)?;
+    let stages = parse_maps(&input)?;
+    let mut lines = vec![];
+
+    for seed in seeds {
+        lines.push(format_steps(&get_seed_steps(&stages, seed)?));
+    }
 
-    get_lowest2(seeds, maps)
+    Ok(lines.join("\n"))
 }
 
-fn get_lowest1(seeds: Vec<isize>, maps: Vec<MapList>) -> usize {
-    let locations = seeds
-        .par_iter()
-        .map(|seed| {
-            let mut needle = *seed;
-
-            for map_list in maps.clone() {
-                let matching_map = map_list
-                    .clone()
-                    .into_iter()
-                    .find(|map| needle >= map.b && needle < map.b + map.len);
-                let modifier = match matching_map.clone() {
-                    Some(map) => map.a - map.b,
-                    None => 0,
-                };
-                let new_needle = needle + modifier;
-
-                if new_needle >= 0 {
-                    needle = new_needle;
-                } else {
-                    panic!("should not be negative")
-                }
-            }
+pub fn render2(input: Vec<String>, _options: &RenderOptions) -> Result<String, SolveError> {
+    let seeds = parse_seeds2(&input)?;
+    let stages = parse_maps(&input)?;
+    let location = get_lowest2(seeds.clone(), &stages)?;
 
-            needle as usize
-        })
-        .collect::<Vec<_>>();
-    let lowest = locations.iter().min().expect("should have a minimum");
+    Ok(format_steps(&get_location_steps(
+        &stages, location, &seeds,
+    )?))
+}
 
-    *lowest
+// Follows a seed through every map, e.g. seed 79, soil 81, ... location 82.
+pub fn trace_seed(input: Vec<String>, seed: u64) -> Result<Vec<Step>, SolveError> {
+    get_seed_steps(&parse_maps(&input)?, seed)
 }
 
-const CHUNK_SIZE: isize = 1_000;
-
-fn get_lowest2(seeds: MapList, maps: Vec<MapList>) -> usize {
-    let mut lowest = usize::MAX;
-
-    for chunk_start in 0_isize.. {
-        let chunk = (chunk_start * CHUNK_SIZE..(chunk_start * CHUNK_SIZE) + CHUNK_SIZE - 1)
-            .collect::<Vec<_>>();
-        let matches = chunk
-            .par_iter()
-            .map(|start| {
-                let mut needle = *start;
-
-                for map_list in maps.clone() {
-                    let matching_map = map_list
-                        .clone()
-                        .into_iter()
-                        .find(|map| needle >= map.a && needle < map.a + map.len);
-                    let modifier = match matching_map.clone() {
-                        Some(map) => map.b - map.a,
-                        None => 0,
-                    };
-                    let new_needle = needle + modifier;
-
-                    if new_needle >= 0 {
-                        needle = new_needle;
-                    } else {
-                        panic!("should not be negative")
-                    }
-                }
+// Follows a location back through every map to a seed in one of the ranges. A value can have
+// several preimages, so this backtracks until it finds a chain that starts at a seed.
+pub fn trace_location(
+    input: Vec<String>,
+    location: u64,
+    seeds: &[Range<u64>],
+) -> Result<Vec<Step>, SolveError> {
+    get_location_steps(&parse_maps(&input)?, location, seeds)
+}
 
-                if seeds
-                    .clone()
-                    .into_iter()
-                    .any(|seed| needle >= seed.a && needle < seed.a + seed.len)
-                {
-                    Some(*start as usize)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-        let matches = matches.into_iter().flatten().collect::<Vec<_>>();
+fn get_seed_steps(stages: &[Stage], seed: u64) -> Result<Vec<Step>, SolveError> {
+    let mut steps = vec![Step {
+        category: get_first_category(stages)?,
+        value: seed,
+    }];
 
-        if let Some(min) = matches.iter().min() {
-            lowest = *min;
+    for stage in stages {
+        let value = steps.last().expect("should have a step").value;
 
-            break;
-        }
+        steps.push(Step {
+            category: stage.destination.clone(),
+            value: stage.map.get(value),
+        });
     }
 
-    lowest
+    Ok(steps)
 }
 
-fn parse_maps(input: Vec<String>) -> Vec<MapList> {
-    let steps = input
-        .join("\n")
-        .split("\n\n")
-        .map(|s| s.trim().to_string())
-        .collect::<Vec<_>>();
-    let mut maps = Vec::new();
-
-    for step in steps {
-        let mut current_map = MapList::new();
-
-        for (i, line) in (0..).zip(
-            step.split('\n')
-                .map(|s| s.trim().to_string())
-                .collect::<Vec<String>>(),
-        ) {
-            if i == 0 {
-                current_map = MapList::new();
-
-                continue;
-            }
+fn get_location_steps(
+    stages: &[Stage],
+    location: u64,
+    seeds: &[Range<u64>],
+) -> Result<Vec<Step>, SolveError> {
+    let last_category = get_last_category(stages)?;
+    let values = find_seed(stages, location, seeds).ok_or_else(|| {
+        SolveError::Unsolvable(format!("no seed leads to {last_category} {location}"))
+    })?;
+    let mut categories = vec![get_first_category(stages)?];
+
+    categories.extend(stages.iter().map(|stage| stage.destination.clone()));
+
+    Ok(categories
+        .into_iter()
+        .zip(values)
+        .map(|(category, value)| Step { category, value })
+        .rev()
+        .collect())
+}
+
+// Returns the values from the seed up to the given one, if any seed reaches it.
+fn find_seed(stages: &[Stage], value: u64, seeds: &[Range<u64>]) -> Option<Vec<u64>> {
+    let Some((stage, previous)) = stages.split_last() else {
+        return seeds
+            .iter()
+            .any(|range| range.contains(&value))
+            .then(|| vec![value]);
+    };
+
+    stage
+        .map
+        .get_preimages(value)
+        .into_iter()
+        .find_map(|preimage| {
+            let mut values = find_seed(previous, preimage, seeds)?;
+
+            values.push(value);
+
+            Some(values)
+        })
+}
+
+fn get_first_category(stages: &[Stage]) -> Result<String, SolveError> {
+    stages
+        .first()
+        .map(|stage| stage.source.clone())
+        .ok_or_else(|| SolveError::Unsolvable("no maps".to_string()))
+}
 
-            let (a, b, len) = line
-                .split(' ')
-                .map(|s| s.parse::<isize>().expect("should be a valid usize"))
-                .collect_tuple()
-                .expect("should be a tuple of three numbers");
+fn get_last_category(stages: &[Stage]) -> Result<String, SolveError> {
+    stages
+        .last()
+        .map(|stage| stage.destination.clone())
+        .ok_or_else(|| SolveError::Unsolvable("no maps".to_string()))
+}
+
+fn format_steps(steps: &[Step]) -> String {
+    steps
+        .iter()
+        .map(|step| format!("{} {}", step.category, step.value))
+        .join(", ")
+}
+
+fn get_lowest1(seeds: Vec<u64>, stages: &[Stage]) -> Result<u64, SolveError> {
+    seeds
+        .into_iter()
+        .map(|seed| {
+            stages
+                .iter()
+                .fold(seed, |value, stage| stage.map.get(value))
+        })
+        .min()
+        .ok_or_else(|| SolveError::Unsolvable("no seeds".to_string()))
+}
 
-            current_map.push(Map { a, b, len });
+fn get_lowest2(seeds: Vec<Range<u64>>, stages: &[Stage]) -> Result<u64, SolveError> {
+    stages
+        .iter()
+        .fold(seeds, |ranges, stage| stage.map.get_ranges(&ranges))
+        .into_iter()
+        .map(|range| range.start)
+        .min()
+        .ok_or_else(|| SolveError::Unsolvable("no seeds".to_string()))
+}
+
+fn parse_maps(input: &[String]) -> Result<Vec<Stage>, ParseError> {
+    static HEADER_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^(?P<source>\w+)-to-(?P<destination>\w+) map:$")
+            .expect("should be a valid regex")
+    });
+
+    let mut stages: Vec<Stage> = Vec::new();
+    let mut current_stage = None;
+
+    for (row, line) in input.iter().enumerate().skip(1) {
+        if line.is_empty() {
+            stages.extend(current_stage.take());
+
+            continue;
         }
 
-        maps.push(current_map);
+        let Some(stage) = current_stage.as_mut() else {
+            let captures = HEADER_REGEX
+                .captures(line)
+                .ok_or_else(|| ParseError::new(row, 0, "<source>-to-<destination> map:"))?;
+
+            // Each map has to pick up where the one before it left off, or the traces would
+            // label values with the wrong categories.
+            if let Some(previous) = stages.last() {
+                if captures["source"] != previous.destination {
+                    return Err(ParseError::new(
+                        row,
+                        0,
+                        &format!("{}-to-<destination> map:", previous.destination),
+                    ));
+                }
+            }
+
+            current_stage = Some(Stage {
+                source: captures["source"].to_string(),
+                destination: captures["destination"].to_string(),
+                map: RangeMap::default(),
+            });
+
+            continue;
+        };
+        let (destination, source, len) = parse_numbers::<u64>(line, ' ', row, 0)?
+            .into_iter()
+            .collect_tuple()
+            .ok_or_else(|| ParseError::new(row, 0, "three numbers"))?;
+
+        // RangeMap adds offsets to both ends, so neither range may run past the end of u64.
+        let end = source
+            .checked_add(len)
+            .filter(|_| destination.checked_add(len).is_some())
+            .ok_or_else(|| ParseError::new(row, 0, "a range that fits in u64"))?;
+
+        stage.map.insert(source..end, destination);
     }
 
-    maps
+    stages.extend(current_stage);
+
+    Ok(stages)
 }
 
-fn parse_seeds1(line: String) -> Vec<isize> {
+fn parse_seeds1(input: &[String]) -> Result<Vec<u64>, ParseError> {
     static SEED_REGEX: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"seeds: (?P<seeds>.*)").expect("should be a valid regex"));
 
-    let captures = SEED_REGEX
-        .captures(line.as_str())
-        .expect("should be able to capture");
+    let line = input.first().map(|s| s.as_str()).unwrap_or("");
+    let seeds = SEED_REGEX
+        .captures(line)
+        .and_then(|captures| captures.name("seeds"))
+        .ok_or_else(|| ParseError::new(0, 0, "seeds: <numbers>"))?;
 
-    captures["seeds"]
-        .split(' ')
-        .map(|s| s.parse::<isize>().expect("should be a valid isize"))
-        .collect()
+    parse_numbers(seeds.as_str(), ' ', 0, seeds.start())
 }
 
-fn parse_seeds2(line: String) -> MapList {
-    let seeds = parse_seeds1(line);
-    let mut maps = MapList::new();
+fn parse_seeds2(input: &[String]) -> Result<Vec<Range<u64>>, ParseError> {
+    let seeds = parse_seeds1(input)?;
+    let mut ranges = vec![];
 
     for chunk in seeds.chunks(2) {
-        if chunk.len() != 2 {
-            panic!("should be a chunk of two");
-        }
+        let Some((start, len)) = chunk.iter().collect_tuple() else {
+            return Err(ParseError::new(0, input[0].len(), "pairs of seed numbers"));
+        };
 
-        let (a, len) = chunk
-            .iter()
-            .collect_tuple()
-            .expect("should be a tuple of two numbers");
+        let end = start
+            .checked_add(*len)
+            .ok_or_else(|| ParseError::new(0, 0, "a range that fits in u64"))?;
 
-        maps.push(Map {
-            a: *a,
-            len: *len,
-            b: 0,
-        });
+        ranges.push(*start..end);
     }
 
-    maps
+    Ok(ranges)
 }
 
 #[cfg(test)]
@@ -245,14 +323,151 @@ mod tests {
                     .map(|s| s.trim().to_string())
                     .collect()
             ),
-            81
+            Ok(81.into())
         );
 
-        assert_eq!(part1(get_fixture()), 35);
+        assert_eq!(part1(get_fixture()), Ok(35.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 46);
+        assert_eq!(part2(get_fixture()), Ok(46.into()));
+    }
+
+    #[test]
+    fn test_trace() {
+        let steps = [
+            ("seed", 79),
+            ("soil", 81),
+            ("fertilizer", 81),
+            ("water", 81),
+            ("light", 74),
+            ("temperature", 78),
+            ("humidity", 78),
+            ("location", 82),
+        ]
+        .map(|(category, value)| Step {
+            category: category.to_string(),
+            value,
+        })
+        .to_vec();
+        let mut reversed = steps.clone();
+
+        reversed.reverse();
+
+        assert_eq!(trace_seed(get_fixture(), 79), Ok(steps));
+        assert_eq!(
+            trace_location(get_fixture(), 82, &[79..93, 55..68]),
+            Ok(reversed)
+        );
+
+        let input = ["seeds: 10 1", "", "seed-to-soil map:", "30 10 5"]
+            .map(|s| s.to_string())
+            .to_vec();
+        let seeds = parse_seeds2(&input).expect("should parse");
+
+        assert_eq!(
+            trace_seed(input.clone(), 10).map(|steps| format_steps(&steps)),
+            Ok("seed 10, soil 30".to_string())
+        );
+        assert_eq!(
+            trace_location(input.clone(), 30, &seeds).map(|steps| format_steps(&steps)),
+            Ok("soil 30, seed 10".to_string())
+        );
+        assert_eq!(
+            trace_location(input, 10, &seeds),
+            Err(SolveError::Unsolvable(
+                "no seed leads to soil 10".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        // Soil 5 comes from seed 7 through the map and from seed 5 unmapped, but only 5 is a seed.
+        let input = ["seeds: 5 1", "", "seed-to-soil map:", "5 7 1"]
+            .map(|s| s.to_string())
+            .to_vec();
+
+        assert_eq!(
+            render2(input, &RenderOptions::default()),
+            Ok("soil 5, seed 5".to_string())
+        );
+        assert_eq!(
+            render2(get_fixture(), &RenderOptions::default()),
+            Ok([
+                "location 46",
+                "humidity 46",
+                "temperature 45",
+                "light 77",
+                "water 84",
+                "fertilizer 84",
+                "soil 84",
+                "seed 82",
+            ]
+            .join(", "))
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let mut input = get_fixture();
+        let mut unchained = input.clone();
+
+        unchained[6] = "fertilizer-to-water map:".to_string();
+
+        assert_eq!(
+            part1(unchained),
+            Err(ParseError::new(6, 0, "soil-to-<destination> map:").into())
+        );
+
+        input[4] = "52 50".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(4, 0, "three numbers").into())
+        );
+
+        input[4] = "0 18446744073709551615 5".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(4, 0, "a range that fits in u64").into())
+        );
+
+        input[4] = "18446744073709551615 0 5".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(4, 0, "a range that fits in u64").into())
+        );
+
+        input[4] = "52 5o 48".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(4, 3, "a number").into())
+        );
+
+        input[2] = "seed to soil map:".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(2, 0, "<source>-to-<destination> map:").into())
+        );
+
+        input[0] = "seeds: 18446744073709551615 2".to_string();
+
+        assert_eq!(
+            part2(input.clone()),
+            Err(ParseError::new(0, 0, "a range that fits in u64").into())
+        );
+
+        input[0] = "seeds: 79 14 55".to_string();
+
+        assert_eq!(
+            part2(input),
+            Err(ParseError::new(0, 15, "pairs of seed numbers").into())
+        );
     }
 }
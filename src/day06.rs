@@ -1,57 +1,67 @@
+use crate::answer::Answer;
+use crate::error::{self, parse_number, ParseError, SolveError};
+use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let times = parse_numbers(input[0].clone());
-    let distances = parse_numbers(input[1].clone());
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let times = parse_numbers(&input, 0)?;
+    let distances = parse_numbers(&input, 1)?;
     let races = times.into_iter().zip(distances).collect::<Vec<_>>();
 
-    get_result(races)
+    Ok(get_result(races).into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let re = Regex::new(r"(\d)\s+").expect("should be a valid regex");
-    let input = input
-        .iter()
-        .map(|line| re.replace_all(line, "$1").to_string())
-        .collect::<Vec<_>>();
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let time = parse_number(&parse_numbers(&input, 0)?.iter().join(""), 0, 0)?;
+    let distance = parse_number(&parse_numbers(&input, 1)?.iter().join(""), 1, 0)?;
 
-    part1(input)
+    Ok(get_result(vec![(time, distance)]).into())
 }
 
 fn get_result(races: Vec<(usize, usize)>) -> usize {
-    let mut result = 1;
-
-    for (time, distance) in races {
-        let mut count = 0;
-
-        for t in 0..=time {
-            let d = (time - t) * t;
+    races
+        .into_iter()
+        .map(|(time, distance)| count_wins(time, distance))
+        .product()
+}
 
-            if d > distance {
-                count += 1;
-            }
+// Holding for t wins when t * (time - t) > distance, which rearranges to
+// (2t - time)^2 < time^2 - 4 * distance. Counting the values of u = 2t - time that fit, with the
+// same parity as time, avoids floating point entirely.
+fn count_wins(time: usize, distance: usize) -> usize {
+    let Some(discriminant) = (time as u128 * time as u128).checked_sub(4 * distance as u128) else {
+        return 0;
+    };
+    let root = discriminant.isqrt();
+    let max = if root * root == discriminant {
+        // An exact root only ties the record, so step inside it.
+        match root.checked_sub(1) {
+            Some(max) => max,
+            None => return 0,
         }
-
-        result *= count;
+    } else {
+        root
+    };
+
+    if max % 2 == time as u128 % 2 {
+        max as usize + 1
+    } else {
+        max as usize
     }
-
-    result
 }
 
-fn parse_numbers(line: String) -> Vec<usize> {
+fn parse_numbers(input: &[String], row: usize) -> Result<Vec<usize>, ParseError> {
     static NUMBERS_REGEX: Lazy<Regex> =
         Lazy::new(|| Regex::new(r".*?: (?P<numbers>.*)").expect("should be a valid regex"));
 
-    let captures = NUMBERS_REGEX
-        .captures(line.as_str())
-        .expect("should be able to capture");
+    let line = input.get(row).map(|s| s.as_str()).unwrap_or("");
+    let numbers = NUMBERS_REGEX
+        .captures(line)
+        .and_then(|captures| captures.name("numbers"))
+        .ok_or_else(|| ParseError::new(row, 0, "<label>: <numbers>"))?;
 
-    captures["numbers"]
-        .split(' ')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.parse::<usize>().expect("should be a valid usize"))
-        .collect()
+    error::parse_numbers(numbers.as_str(), ' ', row, numbers.start())
 }
 
 #[cfg(test)]
@@ -68,11 +78,53 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 288);
+        assert_eq!(part1(get_fixture()), Ok(288.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 71503);
+        assert_eq!(part2(get_fixture()), Ok(71503.into()));
+    }
+
+    fn count_wins_slowly(time: usize, distance: usize) -> usize {
+        (0..=time).filter(|t| t * (time - t) > distance).count()
+    }
+
+    #[test]
+    fn test_count_wins() {
+        assert_eq!(count_wins(7, 9), 4);
+        assert_eq!(count_wins(30, 200), 9);
+        assert_eq!(count_wins(4, 4), 0);
+        assert_eq!(count_wins(0, 0), 0);
+
+        // A fixed-seed xorshift, so failures can be reproduced.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            state as usize % bound
+        };
+
+        for _ in 0..10_000 {
+            let time = next(200);
+            let distance = next(time * time / 4 + 3);
+
+            assert_eq!(
+                count_wins(time, distance),
+                count_wins_slowly(time, distance),
+                "time {time}, distance {distance}"
+            );
+        }
+
+        for time in 0..60 {
+            for distance in 0..=time * time / 4 {
+                assert_eq!(
+                    count_wins(time, distance),
+                    count_wins_slowly(time, distance)
+                );
+            }
+        }
     }
 }
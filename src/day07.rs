@@ -1,3 +1,5 @@
+use crate::answer::Answer;
+use crate::error::{parse_number, ParseError, SolveError};
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -8,15 +10,15 @@ struct Hand {
     score: usize,
 }
 
-pub fn part1(input: Vec<String>) -> usize {
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut hands = Vec::new();
 
-    for line in input {
+    for (row, line) in input.iter().enumerate() {
         let (cards, score) = line
             .split(' ')
             .collect_tuple()
-            .expect("should be a tuple of two");
-        let score = score.parse::<usize>().expect("should be a number");
+            .ok_or_else(|| ParseError::new(row, 0, "<cards> <bid>"))?;
+        let score = parse_number::<usize>(score, row, cards.len() + 1)?;
         let cards = cards.chars().map(|c| match c {
             'T' => 'A',
             'J' => 'B',
@@ -64,7 +66,7 @@ pub fn part1(input: Vec<String>) -> usize {
         let cards = cards
             .into_iter()
             .collect_tuple::<(char, char, char, char, char)>()
-            .expect("should be a tuple of 5");
+            .ok_or_else(|| ParseError::new(row, 0, "five cards"))?;
 
         hands.push(Hand {
             cards,
@@ -86,10 +88,10 @@ pub fn part1(input: Vec<String>) -> usize {
         total += rank * hand.score;
     }
 
-    total
+    Ok(total.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
     let input = input
         .iter()
         .map(|line| line.replace('J', "0"))
@@ -115,11 +117,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 6440);
+        assert_eq!(part1(get_fixture()), Ok(6440.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 5905);
+        assert_eq!(part2(get_fixture()), Ok(5905.into()));
     }
 }
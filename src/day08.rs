@@ -1,21 +1,25 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
 use rayon::prelude::*;
 use regex::Regex;
 use std::collections::HashMap;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let (steps, nodes) = parse_input(input);
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let (steps, nodes) = parse_input(input)?;
     let steps_len = steps.len();
     let mut count = 0;
     let mut current = "AAA".to_string();
 
     loop {
-        let (left, right) = nodes.get(&current).expect("should have a node");
+        let (left, right) = nodes
+            .get(&current)
+            .ok_or_else(|| SolveError::Unsolvable(format!("no node named {current}")))?;
         let direction = steps.get(count % steps_len).expect("should have a step");
 
         current = match direction.as_str() {
             "L" => left.to_owned(),
             "R" => right.to_owned(),
-            _ => panic!("invalid direction"),
+            _ => unreachable!("invalid direction"),
         };
 
         count += 1;
@@ -25,11 +29,11 @@ pub fn part1(input: Vec<String>) -> usize {
         }
     }
 
-    count
+    Ok(count.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let (steps, nodes) = parse_input(input);
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let (steps, nodes) = parse_input(input)?;
     let steps_len = steps.len();
     let currents = nodes
         .keys()
@@ -42,13 +46,15 @@ pub fn part2(input: Vec<String>) -> usize {
             let mut current = *current;
 
             loop {
-                let (left, right) = nodes.get(current).expect("should have a node");
+                let (left, right) = nodes
+                    .get(current)
+                    .ok_or_else(|| SolveError::Unsolvable(format!("no node named {current}")))?;
                 let direction = steps.get(count % steps_len).expect("should have a step");
 
                 current = match direction.as_str() {
                     "L" => left,
                     "R" => right,
-                    _ => panic!("invalid direction"),
+                    _ => unreachable!("invalid direction"),
                 };
 
                 count += 1;
@@ -58,37 +64,53 @@ pub fn part2(input: Vec<String>) -> usize {
                 }
             }
 
-            count
+            Ok(count)
         })
-        .collect::<Vec<_>>();
-    let mut count = numbers[0];
+        .collect::<Result<Vec<_>, SolveError>>()?;
+    let Some(mut count) = numbers.first().copied() else {
+        return Err(SolveError::Unsolvable("no node ends in A".to_string()));
+    };
 
     for &number in numbers.iter().skip(1) {
         count = count * number / num::integer::gcd(count, number);
     }
 
-    count
+    Ok(count.into())
 }
 
-fn parse_input(input: Vec<String>) -> (Vec<String>, HashMap<String, (String, String)>) {
-    let steps = input[0]
+type ParseOutput = (Vec<String>, HashMap<String, (String, String)>);
+
+fn parse_input(input: Vec<String>) -> Result<ParseOutput, ParseError> {
+    let first = input.first().map(|s| s.as_str()).unwrap_or("");
+    let steps = first
         .split("")
         .filter(|s| s != &"")
         .map(|s| s.to_string())
         .collect::<Vec<_>>();
+
+    if let Some(column) = steps.iter().position(|s| s != "L" && s != "R") {
+        return Err(ParseError::new(0, column, "L or R"));
+    }
+
+    if steps.is_empty() {
+        return Err(ParseError::new(0, 0, "L or R"));
+    }
+
     let mut nodes = HashMap::new();
     let node_re = Regex::new(r"(?P<name>\w+) = \((?P<left>\w+), (?P<right>\w+)\)")
         .expect("should be a valid regex");
 
-    for node in input[2..].iter() {
-        let parts = node_re.captures(node).expect("should match regex");
+    for (row, node) in input.iter().enumerate().skip(2) {
+        let parts = node_re
+            .captures(node)
+            .ok_or_else(|| ParseError::new(row, 0, "<node> = (<left>, <right>)"))?;
         nodes.insert(
             parts["name"].to_string(),
             (parts["left"].to_string(), parts["right"].to_string()),
         );
     }
 
-    (steps, nodes)
+    Ok((steps, nodes))
 }
 
 #[cfg(test)]
@@ -139,12 +161,12 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture1()), 2);
-        assert_eq!(part1(get_fixture2()), 6);
+        assert_eq!(part1(get_fixture1()), Ok(2.into()));
+        assert_eq!(part1(get_fixture2()), Ok(6.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture3()), 6);
+        assert_eq!(part2(get_fixture3()), Ok(6.into()));
     }
 }
@@ -1,13 +1,30 @@
+use crate::answer::Answer;
+use crate::error::{parse_numbers, ParseError, SolveError};
 use rayon::prelude::*;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let sum = input
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_sum(input, false)?.into())
+}
+
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_sum(input, true)?.into())
+}
+
+fn get_sum(input: Vec<String>, backwards: bool) -> Result<isize, SolveError> {
+    Ok(input
         .par_iter()
-        .map(|line| {
-            let numbers = line
-                .split(' ')
-                .map(|s| s.parse::<isize>().expect("should be a valid number"))
-                .collect::<Vec<_>>();
+        .enumerate()
+        .map(|(row, line)| {
+            let mut numbers = parse_numbers::<isize>(line, ' ', row, 0)?;
+
+            if numbers.is_empty() {
+                return Err(ParseError::new(row, 0, "a number"));
+            }
+
+            if backwards {
+                numbers.reverse();
+            }
+
             let mut results = vec![numbers];
 
             loop {
@@ -32,31 +49,14 @@ pub fn part1(input: Vec<String>) -> usize {
             let mut result = 0;
 
             for r in results {
-                let last = r.last().expect("should have a last result");
-
-                result += *last;
+                if let Some(last) = r.last() {
+                    result += *last;
+                }
             }
 
-            result
+            Ok(result)
         })
-        .sum::<isize>();
-
-    sum as usize
-}
-
-pub fn part2(input: Vec<String>) -> usize {
-    let input = input
-        .iter()
-        .map(|line| {
-            let mut split = line.split(' ').map(|s| s.to_string()).collect::<Vec<_>>();
-
-            split.reverse();
-
-            split.join(" ")
-        })
-        .collect::<Vec<_>>();
-
-    part1(input)
+        .sum::<Result<isize, ParseError>>()?)
 }
 
 #[cfg(test)]
@@ -74,11 +74,12 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 114);
+        assert_eq!(part1(get_fixture()), Ok(114.into()));
+        assert_eq!(part1(vec!["0 -1 -2".to_string()]), Ok((-3).into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 2);
+        assert_eq!(part2(get_fixture()), Ok(2.into()));
     }
 }
@@ -1,32 +1,30 @@
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::geometry::Direction::{self, Down, Left, Right, Up};
+use crate::grid::{Coords, Grid};
+use std::collections::HashSet;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let (_, path) = get_path(input);
+const PIPES: [char; 6] = ['|', '-', 'L', 'J', '7', 'F'];
 
-    path.len() / 2
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let (_, path) = get_path(input)?;
+
+    Ok((path.len() / 2).into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let line_length = input.first().expect("should have a first line").len();
-    let (first_letter, path) = get_path(input.clone());
-    let grid = input
-        .join("")
-        .replace('S', first_letter.to_string().as_str())
-        .chars()
-        .collect::<Vec<_>>();
-    let grid = grid.chunks(line_length).collect::<Vec<_>>();
-    let boundaries = ['F', '7', 'L', 'J', '|'];
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let (grid, path) = get_path(input)?;
     let mut count = 0;
 
-    for (row_number, row) in (0..).zip(grid) {
+    // Scan each row left to right, flipping between inside and outside every time the loop is
+    // crossed. A run of loop like F--J crosses it, but F--7 only runs along it.
+    for (row, cells) in grid.get_rows().enumerate() {
         let mut inside = false;
         let mut prev_path_cell = '|';
 
-        for (cell_number, cell) in (0..).zip(row) {
-            let grid_number = row_number * line_length + cell_number;
-
-            if path.contains(&grid_number) {
-                if boundaries.contains(cell) {
+        for (column, cell) in cells.iter().enumerate() {
+            if path.contains(&(row, column)) {
+                if *cell != '-' {
                     inside = match (prev_path_cell, cell) {
                         ('F', 'J') => !inside,
                         ('L', '7') => !inside,
@@ -41,92 +39,71 @@ pub fn part2(input: Vec<String>) -> usize {
         }
     }
 
-    count
+    Ok(count.into())
+}
+
+fn get_openings(pipe: char) -> Option<[Direction; 2]> {
+    match pipe {
+        '|' => Some([Up, Down]),
+        '-' => Some([Left, Right]),
+        'L' => Some([Up, Right]),
+        'J' => Some([Up, Left]),
+        '7' => Some([Down, Left]),
+        'F' => Some([Down, Right]),
+        _ => None,
+    }
 }
 
-fn get_path(input: Vec<String>) -> (char, Vec<usize>) {
-    let line_length = input.first().expect("should have a first line").len() as isize;
-    let neg_line_length = 0 - line_length;
-    let grid = input.join("").chars().collect::<Vec<_>>();
+// Returns the grid with the start replaced by the pipe it must be, and every cell on the loop.
+fn get_path(input: Vec<String>) -> Result<(Grid<char>, HashSet<Coords>), SolveError> {
+    let mut grid = Grid::parse(&input, "a pipe", |c| {
+        (PIPES.contains(&c) || c == '.' || c == 'S').then_some(c)
+    })?;
     let start = grid
-        .iter()
-        .position(|c| *c == 'S')
-        .expect("should have a start position") as isize;
-    let mut path = Vec::new();
-    let start_connections = [
-        (-1, ['-', 'L', 'F']),
-        (1, ['-', 'J', '7']),
-        (neg_line_length, ['|', '7', 'F']),
-        (line_length, ['|', 'L', 'J']),
-    ];
-    let start_connections = start_connections
-        .iter()
-        .filter_map(|(direction, valids)| {
-            if valids.contains(grid.get((start + direction) as usize).unwrap_or(&'#')) {
-                Some(direction)
-            } else {
-                None
-            }
+        .position(|cell| *cell == 'S')
+        .ok_or_else(|| SolveError::Unsolvable("no start position".to_string()))?;
+    let start_connections = Direction::ALL
+        .into_iter()
+        .filter(|direction| {
+            grid.get_next(start, *direction)
+                .and_then(|next| get_openings(grid[next]))
+                .is_some_and(|openings| openings.contains(&direction.reverse()))
+        })
+        .collect::<Vec<_>>();
+    let start_pipe = PIPES
+        .into_iter()
+        .find(|pipe| {
+            get_openings(*pipe)
+                .is_some_and(|openings| openings.iter().all(|o| start_connections.contains(o)))
         })
-        .sorted()
-        .collect_tuple::<(&isize, &isize)>()
-        .expect("should have two possible next positions");
-    let first_letter = match (start_connections.0, start_connections.1) {
-        (a, -1) if *a == neg_line_length => 'J',
-        (a, 1) if *a == neg_line_length => 'L',
-        (a, b) if *a == neg_line_length && *b == line_length => '|',
-        (-1, 1) => '-',
-        (-1, b) if *b == line_length => '7',
-        (1, b) if *b == line_length => 'F',
-        _ => panic!("should have a first letter"),
-    };
-    let mut current_and_direction = (start, *start_connections.0);
+        .filter(|_| start_connections.len() == 2)
+        .ok_or_else(|| {
+            SolveError::Unsolvable("the start should connect to exactly two pipes".to_string())
+        })?;
+    let mut path = HashSet::from([start]);
+    let mut current = start;
+    let mut direction = start_connections[0];
+
+    grid[start] = start_pipe;
 
     loop {
-        let current = current_and_direction.0;
-        let direction = current_and_direction.1;
-
-        path.push((current + direction) as usize);
-
-        let next_direction = match grid.get((current + direction) as usize) {
-            Some('-') => direction,
-            Some('|') => direction,
-            Some('L') => {
-                if direction == -1 {
-                    neg_line_length
-                } else {
-                    1
-                }
-            }
-            Some('J') => {
-                if direction == 1 {
-                    neg_line_length
-                } else {
-                    -1
-                }
-            }
-            Some('7') => {
-                if direction == 1 {
-                    line_length
-                } else {
-                    -1
-                }
-            }
-            Some('F') => {
-                if direction == -1 {
-                    line_length
-                } else {
-                    1
-                }
-            }
-            Some('S') => break,
-            _ => panic!("off the grid"),
-        };
+        current = grid.get_next(current, direction).ok_or_else(|| {
+            SolveError::Unsolvable("the loop should not leave the grid".to_string())
+        })?;
+
+        if current == start {
+            break;
+        }
+
+        path.insert(current);
 
-        current_and_direction = (current + direction, next_direction);
+        direction = get_openings(grid[current])
+            .filter(|openings| openings.contains(&direction.reverse()))
+            .and_then(|openings| openings.into_iter().find(|o| *o != direction.reverse()))
+            .ok_or_else(|| SolveError::Unsolvable("the loop should not be broken".to_string()))?;
     }
 
-    (first_letter, path)
+    Ok((grid, path))
 }
 
 #[cfg(test)]
@@ -177,12 +154,12 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture1()), 4);
+        assert_eq!(part1(get_fixture1()), Ok(4.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture2()), 4);
-        assert_eq!(part2(get_fixture3()), 10);
+        assert_eq!(part2(get_fixture2()), Ok(4.into()));
+        assert_eq!(part2(get_fixture3()), Ok(10.into()));
     }
 }
@@ -1,56 +1,46 @@
-type GalaxyList = Vec<Galaxy>;
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::geometry::Point;
+use crate::grid::Grid;
 
-#[derive(Debug, Clone, Copy)]
-struct Galaxy {
-    row: usize,
-    col: usize,
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_total(input, 2)?.into())
 }
 
-pub fn part1(input: Vec<String>) -> usize {
-    get_total(input, 2)
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_total(input, 1_000_000)?.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    get_total(input, 1_000_000)
-}
-
-fn get_total(input: Vec<String>, rate: usize) -> usize {
-    let mut cols = vec![rate; input[0].len()];
-    let mut rows = vec![rate; input.len()];
-
-    for (row, cells) in (0..).zip(input.clone()) {
-        for (col, cell) in (0..).zip(cells.chars()) {
-            if cell == '#' {
-                cols[col] = 1;
-                rows[row] = 1;
-            }
-        }
-    }
-
-    let mut galaxies = GalaxyList::new();
+fn get_total(input: Vec<String>, rate: usize) -> Result<usize, ParseError> {
+    let grid = Grid::parse(&input, "# or .", |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+    let rows = grid
+        .get_rows()
+        .map(|cells| if cells.contains(&true) { 1 } else { rate })
+        .collect::<Vec<_>>();
+    let cols = grid
+        .get_columns()
+        .map(|mut cells| if cells.any(|cell| *cell) { 1 } else { rate })
+        .collect::<Vec<_>>();
+    let mut galaxies = Vec::<Point>::new();
     let mut total = 0;
 
-    for (origin_row, cells) in (0..).zip(input.clone()) {
+    for ((origin_row, origin_col), _) in grid.iter().filter(|(_, cell)| **cell) {
         let row = rows[0..origin_row].iter().sum::<usize>();
+        let col = cols[0..origin_col].iter().sum::<usize>();
+        let point = Point::new(col as isize, row as isize);
 
-        for (origin_col, cell) in (0..).zip(cells.chars()) {
-            let col = cols[0..origin_col].iter().sum::<usize>();
-
-            if cell == '#' {
-                for galaxy in galaxies.clone() {
-                    let mut sub_total = galaxy.col.abs_diff(col);
-
-                    sub_total += row - galaxy.row;
-
-                    total += sub_total;
-                }
-
-                galaxies.push(Galaxy { row, col });
-            }
+        for galaxy in &galaxies {
+            total += galaxy.get_manhattan_distance(point);
         }
+
+        galaxies.push(point);
     }
 
-    total
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -75,10 +65,10 @@ mod tests {
 
     #[test]
     fn test_get_total() {
-        assert_eq!(get_total(get_fixture(), 2), 374);
+        assert_eq!(get_total(get_fixture(), 2), Ok(374));
 
-        assert_eq!(get_total(get_fixture(), 10), 1030);
+        assert_eq!(get_total(get_fixture(), 10), Ok(1030));
 
-        assert_eq!(get_total(get_fixture(), 100), 8410);
+        assert_eq!(get_total(get_fixture(), 100), Ok(8410));
     }
 }
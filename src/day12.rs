@@ -1,31 +1,41 @@
+use crate::answer::Answer;
+use crate::error::{parse_numbers, ParseError, SolveError};
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::HashMap;
 
-pub fn part1(input: Vec<String>) -> usize {
-    get_total(input, 1) as usize
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_total(input, 1)?.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    get_total(input, 5) as usize
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_total(input, 5)?.into())
 }
 
-fn get_total(input: Vec<String>, fold: u128) -> u128 {
+fn get_total(input: Vec<String>, fold: u128) -> Result<u128, ParseError> {
     let total = input
         .par_iter()
-        .map(|line| {
-            let parts = line.split(' ').map(|s| s.to_string()).collect::<Vec<_>>();
-            let (left, right) = parts
-                .iter()
-                .collect_tuple::<(&String, &String)>()
-                .expect("a tuple of two");
+        .enumerate()
+        .map(|(row, line)| {
+            let (left, right) = line
+                .split(' ')
+                .collect_tuple::<(&str, &str)>()
+                .ok_or_else(|| ParseError::new(row, 0, "<springs> <groups>"))?;
+
+            if let Some((column, _)) = left.chars().find_position(|c| !['?', '#', '.'].contains(c))
+            {
+                return Err(ParseError::new(row, column, "?, # or ."));
+            }
+
+            let blocks = parse_numbers::<u128>(right, ',', row, left.len() + 1)?;
+
+            if blocks.is_empty() {
+                return Err(ParseError::new(row, left.len() + 1, "a number"));
+            }
+
             let left = vec![left; fold as usize].iter().join("?");
             let length = left.len() as u128;
-            let right = vec![right; fold as usize].iter().join(",");
-            let blocks = right
-                .split(',')
-                .map(|s| s.parse::<u128>().expect("should be a number"))
-                .collect_vec();
+            let blocks = blocks.repeat(fold as usize);
             let mut pattern = 0;
             let mut anti_pattern = 0;
 
@@ -46,11 +56,11 @@ fn get_total(input: Vec<String>, fold: u128) -> u128 {
             let total =
                 combinations.calculate(0, 0, &blocks, blocks.iter().sum(), blocks.len() as u128);
 
-            total
+            Ok(total)
         })
-        .sum::<u128>();
+        .sum::<Result<u128, ParseError>>()?;
 
-    total
+    Ok(total)
 }
 
 struct Combinations {
@@ -134,7 +144,7 @@ mod tests {
 
     #[test]
     fn test_get_total() {
-        assert_eq!(get_total(get_fixture(), 1), 21);
-        assert_eq!(get_total(get_fixture(), 5), 525152);
+        assert_eq!(get_total(get_fixture(), 1), Ok(21));
+        assert_eq!(get_total(get_fixture(), 5), Ok(525152));
     }
 }
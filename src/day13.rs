@@ -1,89 +1,76 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
 use rayon::prelude::*;
 
-pub fn part1(input: Vec<String>) -> usize {
-    get_result(input, 0) as usize
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_result(input, 0)?.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    get_result(input, 1) as usize
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_result(input, 1)?.into())
 }
 
-fn get_result(input: Vec<String>, target: u32) -> u32 {
-    let input = input.join("\n");
-    let input = input.split("\n\n").collect::<Vec<_>>();
+fn get_result(input: Vec<String>, target: u32) -> Result<u32, ParseError> {
+    let mut grids = vec![];
+    let mut start = 0;
 
-    input
-        .par_iter()
-        .map(|block| {
-            let lines = block.split('\n').collect::<Vec<_>>();
-            let mut columns = Vec::new();
-            let mut rows = Vec::new();
-
-            for (y, line) in (0..).zip(lines.clone()) {
-                rows.push(0);
-
-                for (x, c) in (0..).zip(line.chars()) {
-                    if columns.len() <= x {
-                        columns.push(0);
-                    }
-
-                    if c == '#' {
-                        rows[y] |= 1 << x;
-
-                        columns[x] |= 1 << y;
-                    }
-                }
-            }
-
-            'o: for i in 0..rows.len() - 1 {
-                let diff = rows[i] ^ rows[i + 1];
-                let diff = (diff as u32).count_ones();
-
-                if diff == 0 || diff == target {
-                    let mut current = 0;
+    for (row, line) in input.iter().chain([&String::new()]).enumerate() {
+        if !line.is_empty() {
+            continue;
+        }
 
-                    for (a, b) in (0..=i).rev().zip(i + 1..) {
-                        if let (Some(a), Some(b)) = (rows.get(a), rows.get(b)) {
-                            let diff = ((a ^ b) as u32).count_ones();
-
-                            current += diff;
-                        }
-                    }
+        let grid = Grid::parse(&input[start..row], "# or .", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .map_err(|error| ParseError {
+            line: error.line + start,
+            ..error
+        })?;
 
-                    if current != target {
-                        continue 'o;
-                    }
+        grids.push(grid);
+        start = row + 1;
+    }
 
-                    return ((i + 1) * 100) as u32;
-                }
+    Ok(grids
+        .par_iter()
+        .map(|grid| {
+            if let Some(row) = get_reflection(grid, target) {
+                return row * 100;
             }
 
-            'o: for i in 0..columns.len() - 1 {
-                let diff = columns[i] ^ columns[i + 1];
-                let diff = (diff as u32).count_ones();
-
-                if diff == 0 || diff == target {
-                    let mut current = 0;
-
-                    for (a, b) in (0..=i).rev().zip(i + 1..) {
-                        if let (Some(a), Some(b)) = (columns.get(a), columns.get(b)) {
-                            let diff = ((a ^ b) as u32).count_ones();
-
-                            current += diff;
-                        }
-                    }
+            get_reflection(&grid.transpose(), target).unwrap_or(0)
+        })
+        .sum())
+}
 
-                    if current != target {
-                        continue 'o;
-                    }
+// Finds the row with a mirror just below it, where the two reflected halves differ in exactly
+// `target` cells.
+fn get_reflection(grid: &Grid<bool>, target: u32) -> Option<u32> {
+    let rows = grid
+        .get_rows()
+        .map(|cells| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, cell)| **cell)
+                .fold(0_u128, |row, (x, _)| row | 1 << x)
+        })
+        .collect::<Vec<_>>();
 
-                    return (i + 1) as u32;
-                }
-            }
+    (1..rows.len())
+        .find(|i| {
+            let diff = (0..*i)
+                .rev()
+                .zip(*i..rows.len())
+                .map(|(a, b)| (rows[a] ^ rows[b]).count_ones())
+                .sum::<u32>();
 
-            0_u32
+            diff == target
         })
-        .sum()
+        .map(|i| i as u32)
 }
 
 #[cfg(test)]
@@ -113,11 +100,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 405);
+        assert_eq!(part1(get_fixture()), Ok(405.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 400);
+        assert_eq!(part2(get_fixture()), Ok(400.into()));
     }
 }
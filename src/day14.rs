@@ -1,49 +1,49 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
 use std::collections::{hash_map::Entry::Vacant, HashMap};
 
-pub fn part1(input: Vec<String>) -> usize {
-    let mut tilter = Tilter {
-        state: input.clone(),
-    };
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let mut tilter = Tilter::parse(input)?;
 
     tilter.tilt_north();
 
-    tilter.get_weight()
+    Ok(tilter.get_weight().into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let mut tilter = Tilter {
-        state: input.clone(),
-    };
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let mut tilter = Tilter::parse(input)?;
 
     tilter.spin_cycle(1_000_000_000);
 
-    tilter.get_weight()
+    Ok(tilter.get_weight().into())
 }
 
-#[derive(Default)]
 struct Tilter {
-    state: Vec<String>,
+    grid: Grid<char>,
 }
 
 impl Tilter {
-    fn get_weight(&mut self) -> usize {
-        let mut result = 0;
-        let state = self.state.clone();
-        let input_length = self.state.len();
+    fn parse(input: Vec<String>) -> Result<Self, ParseError> {
+        let grid = Grid::parse(&input, "O, # or .", |c| {
+            ['O', '#', '.'].contains(&c).then_some(c)
+        })?;
 
-        for (weight, line) in (1..=input_length).rev().zip(state) {
-            for c in line.chars() {
-                if c == 'O' {
-                    result += weight;
-                }
-            }
-        }
+        Ok(Self { grid })
+    }
 
-        result
+    fn get_weight(&self) -> usize {
+        let height = self.grid.get_height();
+
+        self.grid
+            .get_rows()
+            .enumerate()
+            .map(|(row, cells)| (height - row) * cells.iter().filter(|c| **c == 'O').count())
+            .sum()
     }
 
     fn spin_cycle(&mut self, cycles: usize) {
-        let mut map = HashMap::<Vec<String>, usize>::new();
+        let mut map = HashMap::<Grid<char>, usize>::new();
         let mut loop_found = false;
         let mut count = 0;
 
@@ -53,155 +53,43 @@ impl Tilter {
             }
 
             if !loop_found {
-                if let Vacant(e) = map.entry(self.state.clone()) {
+                if let Vacant(e) = map.entry(self.grid.clone()) {
                     e.insert(count);
                 } else {
-                    let loop_length =
-                        count - map.get(&self.state.clone()).expect("should have a number");
+                    let loop_length = count - map[&self.grid];
 
                     count += loop_length * ((cycles - count) / loop_length) - loop_length;
-
                     loop_found = true;
                 }
             }
 
-            self.tilt_north();
-            self.tilt_west();
-            self.tilt_south();
-            self.tilt_east();
+            // Rotating clockwise after each tilt brings west, then south, then east to the top,
+            // and the fourth rotation puts the grid back the way it started.
+            for _ in 0..4 {
+                self.tilt_north();
+                self.grid = self.grid.rotate();
+            }
 
             count += 1;
         }
     }
 
     fn tilt_north(&mut self) {
-        let mut columns = HashMap::new();
-        let mut output = self.state.clone();
-        let state = self.state.clone();
-
-        for (row, line) in (0..).zip(state) {
-            for (column, c) in (0..).zip(line.chars()) {
-                if c == '#' {
-                    columns.insert(column, row);
-                } else if c == 'O' {
-                    output[row].replace_range(column..column + 1, ".");
-
-                    let row = columns.get(&column);
-
-                    if let Some(row) = row {
-                        output[row + 1].replace_range(column..column + 1, "O");
-
-                        columns.insert(column, row + 1);
-                    } else {
-                        output[0].replace_range(column..column + 1, "O");
-
-                        columns.insert(column, 0);
-                    }
-                }
-            }
-        }
-
-        self.state = output;
-    }
-
-    fn tilt_south(&mut self) {
-        let mut columns = HashMap::new();
-        let mut output = self.state.clone();
-        let input_length = self.state.len();
-        let state = self.state.clone();
-
-        for (row, line) in (0..input_length).zip(state).rev() {
-            for (column, c) in (0..).zip(line.chars()) {
-                if c == '#' {
-                    columns.insert(column, row);
-                } else if c == 'O' {
-                    output[row].replace_range(column..column + 1, ".");
-
-                    let row = columns.get(&column);
-
-                    if let Some(row) = row {
-                        output[row - 1].replace_range(column..column + 1, "O");
-
-                        columns.insert(column, row - 1);
-                    } else {
-                        output[input_length - 1].replace_range(column..column + 1, "O");
-
-                        columns.insert(column, input_length - 1);
+        for column in 0..self.grid.get_width() {
+            let mut next = 0;
+
+            for row in 0..self.grid.get_height() {
+                match self.grid[(row, column)] {
+                    '#' => next = row + 1,
+                    'O' => {
+                        self.grid[(row, column)] = '.';
+                        self.grid[(next, column)] = 'O';
+                        next += 1;
                     }
+                    _ => (),
                 }
             }
         }
-
-        self.state = output;
-    }
-
-    fn tilt_east(&mut self) {
-        let mut output = self.state.clone();
-        let state = self.state.clone();
-
-        for (row, line) in (0..).zip(state) {
-            let mut next = None;
-
-            for (column, c) in (0..line.len())
-                .zip(line.chars())
-                .collect::<Vec<_>>()
-                .iter()
-                .rev()
-            {
-                let column = *column;
-                let c = *c;
-
-                if c == '#' {
-                    next = Some(column);
-                } else if c == 'O' {
-                    output[row].replace_range(column..column + 1, ".");
-
-                    if let Some(column) = next {
-                        output[row].replace_range(column - 1..column, "O");
-
-                        next = Some(column - 1);
-                    } else {
-                        output[row].replace_range(line.len() - 1..line.len(), "O");
-
-                        next = Some(line.len() - 1);
-                    }
-                }
-            }
-        }
-
-        self.state = output;
-    }
-
-    fn tilt_west(&mut self) {
-        let mut output = self.state.clone();
-        let state = self.state.clone();
-
-        for (row, line) in (0..).zip(state) {
-            let mut next = None;
-
-            for (column, c) in (0..).zip(line.chars()) {
-                let column = column.to_owned();
-                let c = c.to_owned();
-
-                if c == '#' {
-                    next = Some(column);
-                } else if c == 'O' {
-                    output[row].replace_range(column..column + 1, ".");
-
-                    if let Some(column) = next {
-                        output[row].replace_range(column + 1..column + 2, "O");
-
-                        next = Some(column + 1);
-                    } else {
-                        output[row].replace_range(0..1, "O");
-
-                        next = Some(0);
-                    }
-                }
-            }
-        }
-
-        self.state = output;
     }
 }
 
@@ -242,22 +130,20 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture1()), 136);
+        assert_eq!(part1(get_fixture1()), Ok(136.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture1()), 64);
+        assert_eq!(part2(get_fixture1()), Ok(64.into()));
     }
 
     #[test]
     fn test_spin_cycle() {
-        let mut tilter = Tilter {
-            state: get_fixture1(),
-        };
+        let mut tilter = Tilter::parse(get_fixture1()).expect("should parse");
 
         tilter.spin_cycle(3);
 
-        assert_eq!(tilter.state, get_fixture2());
+        assert_eq!(tilter.grid.to_string(), get_fixture2().join("\n"));
     }
 }
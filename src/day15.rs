@@ -1,8 +1,13 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
 use itertools::Itertools;
 use rayon::prelude::*;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let values = input[0]
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let values = input
+        .first()
+        .map(|s| s.as_str())
+        .unwrap_or("")
         .split(',')
         .map(|s| s.chars().collect::<Vec<_>>())
         .collect::<Vec<_>>();
@@ -11,7 +16,7 @@ pub fn part1(input: Vec<String>) -> usize {
         .map(|v| v.iter().fold(0_u128, hash))
         .sum::<u128>();
 
-    result as usize
+    Ok(result.into())
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -29,43 +34,40 @@ struct Operation {
 
 type Item = (Vec<u128>, u128);
 
-pub fn part2(input: Vec<String>) -> usize {
-    let values = input[0]
-        .split(',')
-        .map(|s| s.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-    let operations = values
-        .iter()
-        .map(|values| {
-            let mut operation = Operation {
-                label: vec![],
-                hash: 0,
-                operation_type: OperationType::Remove,
-            };
-
-            for value in values {
-                if *value == '-' {
-                    break;
-                } else if *value == '=' {
-                    operation.operation_type = OperationType::Insert(0_u128);
-                } else if let OperationType::Insert(len) = operation.operation_type {
-                    operation.operation_type = OperationType::Insert(
-                        (len * 10)
-                            + value
-                                .to_string()
-                                .parse::<u128>()
-                                .expect("should be parsable as number"),
-                    )
-                } else {
-                    operation.label.push(*value as u128);
-
-                    operation.hash = hash(operation.hash, value);
-                }
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let line = input.first().map(|s| s.as_str()).unwrap_or("");
+    let mut operations = vec![];
+    let mut offset = 0;
+
+    for step in line.split(',') {
+        let mut operation = Operation {
+            label: vec![],
+            hash: 0,
+            operation_type: OperationType::Remove,
+        };
+
+        for (column, value) in (offset..).zip(step.chars()) {
+            if value == '-' {
+                break;
+            } else if value == '=' {
+                operation.operation_type = OperationType::Insert(0_u128);
+            } else if let OperationType::Insert(len) = operation.operation_type {
+                let digit = value
+                    .to_digit(10)
+                    .ok_or_else(|| ParseError::new(0, column, "a focal length"))?;
+
+                operation.operation_type = OperationType::Insert((len * 10) + digit as u128)
+            } else {
+                operation.label.push(value as u128);
+
+                operation.hash = hash(operation.hash, &value);
             }
+        }
+
+        operations.push(operation);
+        offset += step.len() + 1;
+    }
 
-            operation
-        })
-        .collect::<Vec<_>>();
     let mut boxes = vec![Vec::<Item>::new(); 256];
 
     for operation in operations {
@@ -98,14 +100,14 @@ pub fn part2(input: Vec<String>) -> usize {
             let mut subtotal = 0;
 
             for (j, (_, len)) in items.iter().enumerate() {
-                subtotal += (i + 1) * (j + 1) * (*len as usize);
+                subtotal += (i as u128 + 1) * (j as u128 + 1) * len;
             }
 
             subtotal
         })
-        .sum::<usize>();
+        .sum::<u128>();
 
-    total
+    Ok(total.into())
 }
 
 fn hash(acc: u128, curr: &char) -> u128 {
@@ -131,11 +133,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 1320);
+        assert_eq!(part1(get_fixture()), Ok(1320.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 145);
+        assert_eq!(part2(get_fixture()), Ok(145.into()));
     }
 }
@@ -1,148 +1,86 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::geometry::Direction;
+use crate::grid::{Coords, Grid};
 use rayon::prelude::*;
 use std::collections::HashSet;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-type Move = (usize, usize, Direction);
+type Move = (Coords, Direction);
 type MoveList = Vec<Move>;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let grid = Grid {
-        cells: input
-            .iter()
-            .map(|s| s.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>(),
-    };
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let grid = parse_grid(input)?;
 
-    grid.get_energized(0, 0, Direction::Right)
+    Ok(get_energized(&grid, ((0, 0), Direction::Right)).into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
     let mut starts = MoveList::new();
-    let grid = Grid {
-        cells: input
-            .iter()
-            .map(|s| s.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>(),
-    };
+    let grid = parse_grid(input)?;
+    let row_max = grid.get_height() - 1;
+    let column_max = grid.get_width() - 1;
 
-    for x in 0..grid.get_x_max() {
-        starts.push((x, 0, Direction::Down));
-        starts.push((x, grid.get_y_max(), Direction::Up));
+    for column in 0..=column_max {
+        starts.push(((0, column), Direction::Down));
+        starts.push(((row_max, column), Direction::Up));
     }
 
-    for y in 0..grid.get_y_max() {
-        starts.push((0, y, Direction::Right));
-        starts.push((grid.get_x_max(), y, Direction::Left));
+    for row in 0..=row_max {
+        starts.push(((row, 0), Direction::Right));
+        starts.push(((row, column_max), Direction::Left));
     }
 
     let result = starts
         .par_iter()
-        .map(|(x, y, dir)| grid.get_energized(*x, *y, *dir))
+        .map(|start| get_energized(&grid, *start))
         .max()
         .expect("should have a max");
 
-    result
+    Ok(result.into())
 }
 
-struct Grid {
-    cells: Vec<Vec<char>>,
+fn parse_grid(input: Vec<String>) -> Result<Grid<char>, SolveError> {
+    Ok(Grid::parse(&input, "a mirror, splitter or .", |c| {
+        ['.', '/', '\\', '|', '-'].contains(&c).then_some(c)
+    })?)
 }
 
-impl Grid {
-    fn get_y_max(&self) -> usize {
-        self.cells.len() - 1
-    }
+fn get_energized(grid: &Grid<char>, start: Move) -> usize {
+    let mut prev_moves = HashSet::<Move>::new();
+    let mut energized = HashSet::<Coords>::new();
+    let mut moves = get_new_moves(grid, start);
 
-    fn get_x_max(&self) -> usize {
-        self.cells[0].len() - 1
-    }
+    while !moves.is_empty() {
+        let mut new_moves = MoveList::new();
 
-    fn get_energized(&self, x: usize, y: usize, dir: Direction) -> usize {
-        let y_max = self.get_y_max();
-        let x_max = self.get_x_max();
-        let mut prev_moves = HashSet::<Move>::new();
-        let mut energized = HashSet::<(usize, usize)>::new();
-        let mut moves: MoveList = self.get_new_moves(x, y, dir);
-
-        while !moves.is_empty() {
-            let mut new_moves = MoveList::new();
-
-            for (x, y, dir) in moves {
-                if prev_moves.contains(&(x, y, dir)) {
-                    continue;
-                }
-
-                prev_moves.insert((x, y, dir));
-                energized.insert((x, y));
-
-                let new_coords = match dir {
-                    Direction::Up if y > 0 => Some((x, y - 1)),
-                    Direction::Down if y < y_max => Some((x, y + 1)),
-                    Direction::Left if x > 0 => Some((x - 1, y)),
-                    Direction::Right if x < x_max => Some((x + 1, y)),
-                    _ => None,
-                };
-
-                if let Some((x, y)) = new_coords {
-                    new_moves.append(self.get_new_moves(x, y, dir).as_mut())
-                }
+        for (coords, dir) in moves {
+            if !prev_moves.insert((coords, dir)) {
+                continue;
             }
 
-            moves = new_moves;
-        }
-
-        energized.len()
-    }
+            energized.insert(coords);
 
-    fn get_new_moves(&self, x: usize, y: usize, dir: Direction) -> MoveList {
-        let mut new_moves = MoveList::new();
-        match (self.cells[y][x], dir) {
-            ('|', Direction::Left) | ('|', Direction::Right) => {
-                new_moves.push((x, y, Direction::Up));
-                new_moves.push((x, y, Direction::Down));
-            }
-            ('-', Direction::Up) | ('-', Direction::Down) => {
-                new_moves.push((x, y, Direction::Left));
-                new_moves.push((x, y, Direction::Right));
-            }
-            ('/', Direction::Up) => {
-                new_moves.push((x, y, Direction::Right));
-            }
-            ('/', Direction::Down) => {
-                new_moves.push((x, y, Direction::Left));
-            }
-            ('/', Direction::Left) => {
-                new_moves.push((x, y, Direction::Down));
-            }
-            ('/', Direction::Right) => {
-                new_moves.push((x, y, Direction::Up));
-            }
-            ('\\', Direction::Up) => {
-                new_moves.push((x, y, Direction::Left));
-            }
-            ('\\', Direction::Down) => {
-                new_moves.push((x, y, Direction::Right));
-            }
-            ('\\', Direction::Left) => {
-                new_moves.push((x, y, Direction::Up));
-            }
-            ('\\', Direction::Right) => {
-                new_moves.push((x, y, Direction::Down));
-            }
-            _ => {
-                new_moves.push((x, y, dir));
+            if let Some(next) = grid.get_next(coords, dir) {
+                new_moves.extend(get_new_moves(grid, (next, dir)));
             }
         }
 
-        new_moves
+        moves = new_moves;
     }
+
+    energized.len()
+}
+
+// A beam entering a splitter side-on leaves both ways, and a mirror turns it by a right angle.
+fn get_new_moves(grid: &Grid<char>, (coords, dir): Move) -> MoveList {
+    let dirs = match (grid[coords], dir.is_vertical()) {
+        ('|', false) | ('-', true) => vec![dir.turn_left(), dir.turn_right()],
+        ('/', true) | ('\\', false) => vec![dir.turn_right()],
+        ('/', false) | ('\\', true) => vec![dir.turn_left()],
+        _ => vec![dir],
+    };
+
+    dirs.into_iter().map(|dir| (coords, dir)).collect()
 }
 
 #[cfg(test)]
@@ -167,11 +105,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 46);
+        assert_eq!(part1(get_fixture()), Ok(46.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 51);
+        assert_eq!(part2(get_fixture()), Ok(51.into()));
     }
 }
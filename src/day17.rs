@@ -1,170 +1,119 @@
-use itertools::Itertools;
-use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-
-type Key = (usize, usize, bool);
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::geometry::Direction;
+use crate::grid::{Coords, Grid};
+use crate::pathfinding::{self, Path};
+use crate::registry::RenderOptions;
+use std::cmp::Ordering;
+
+// Where the crucible is and whether it arrived there moving vertically.
+type State = (Coords, bool);
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(dijkstra(input, 1, 3)?.cost.into())
+}
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Node {
-    weight: usize,
-    edges: Vec<Vec<Key>>,
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(dijkstra(input, 4, 10)?.cost.into())
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Limits {
-    x: usize,
-    y: usize,
-    min: usize,
-    max: usize,
+pub fn render1(input: Vec<String>, _options: &RenderOptions) -> Result<String, SolveError> {
+    render(input, 1, 3)
 }
 
-fn get_edges(row: usize, column: usize, lateral: bool, limits: Limits) -> Vec<Vec<Key>> {
-    let edges: Vec<Vec<Key>> = match lateral {
-        false => {
-            let mut columns = vec![];
+pub fn render2(input: Vec<String>, _options: &RenderOptions) -> Result<String, SolveError> {
+    render(input, 4, 10)
+}
 
-            for size in limits.min..=limits.max {
-                if column as isize > size as isize - 1 {
-                    columns.push((1..=size).map(|i| column - i).collect_vec());
-                }
+// Draws the route over the map the way the puzzle does, with an arrow on each block showing the
+// direction the crucible entered it.
+pub fn render(input: Vec<String>, min: usize, max: usize) -> Result<String, SolveError> {
+    let path = dijkstra(input.clone(), min, max)?;
+    let mut grid = Grid::parse(&input, "a digit", |c| c.is_ascii_digit().then_some(c))?;
+
+    for pair in path.nodes.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+
+        grid[to] = match (to.0.cmp(&from.0), to.1.cmp(&from.1)) {
+            (Ordering::Less, _) => '^',
+            (Ordering::Greater, _) => 'v',
+            (_, Ordering::Less) => '<',
+            _ => '>',
+        };
+    }
 
-                if (column as isize) < limits.x as isize - (size as isize - 1) {
-                    columns.push((1..=size).map(|i| column + i).collect_vec());
-                }
-            }
+    Ok(grid.to_string())
+}
 
-            columns
-                .iter()
-                .map(|keys| {
-                    keys.iter()
-                        .map(|column| (row.to_owned(), column.to_owned(), lateral))
-                        .collect_vec()
-                })
-                .collect()
+// Returns the heat lost on the best route and every block along it, starting from the top left.
+pub fn dijkstra(input: Vec<String>, min: usize, max: usize) -> Result<Path<Coords>, SolveError> {
+    let grid = Grid::parse(&input, "a digit", |c| c.to_digit(10).map(|d| d as usize))?;
+    let end = (grid.get_height() - 1, grid.get_width() - 1);
+    let path = pathfinding::dijkstra(
+        [((0, 0), true), ((0, 0), false)],
+        |state| get_successors(&grid, *state, min, max),
+        |(coords, _)| *coords == end,
+    )
+    .ok_or_else(|| SolveError::Unsolvable("no route to the factory".to_string()))?;
+    let mut nodes = vec![(0, 0)];
+
+    for ((from, _), (to, _)) in path.nodes.iter().zip(path.nodes.iter().skip(1)) {
+        let mut current = *from;
+
+        while current != *to {
+            current = (step_towards(current.0, to.0), step_towards(current.1, to.1));
+            nodes.push(current);
         }
-        true => {
-            let mut rows = vec![];
-
-            for size in limits.min..=limits.max {
-                if row as isize > size as isize - 1 {
-                    rows.push((1..=size).map(|i| row - i).collect_vec());
-                }
+    }
 
-                if (row as isize) < limits.y as isize - (size as isize - 1) {
-                    rows.push((1..=size).map(|i| row + i).collect_vec());
-                }
-            }
+    Ok(Path {
+        cost: path.cost,
+        nodes,
+    })
+}
 
-            rows.iter()
-                .map(|keys| {
-                    keys.iter()
-                        .map(|row| (row.to_owned(), column.to_owned(), lateral))
-                        .collect_vec()
-                })
-                .collect()
-        }
+// The crucible has to turn at every state, so the successors are the ends of the straight runs of
+// min to max blocks to either side of the way it arrived.
+fn get_successors(
+    grid: &Grid<usize>,
+    (coords, vertical): State,
+    min: usize,
+    max: usize,
+) -> Vec<(State, usize)> {
+    let directions = if vertical {
+        [Direction::Left, Direction::Right]
+    } else {
+        [Direction::Up, Direction::Down]
     };
+    let mut successors = vec![];
 
-    edges
-}
+    for direction in directions {
+        let mut current = coords;
+        let mut cost = 0;
 
-pub fn part1(input: Vec<String>) -> usize {
-    dijkstra(input, 1, 3)
-}
+        for length in 1..=max {
+            let Some(next) = grid.get_next(current, direction) else {
+                break;
+            };
 
-pub fn part2(input: Vec<String>) -> usize {
-    dijkstra(input, 4, 10)
-}
+            current = next;
+            cost += grid[current];
 
-pub fn dijkstra(input: Vec<String>, min: usize, max: usize) -> usize {
-    let limits = Limits {
-        x: input[0].len() - 1,
-        y: input.len() - 1,
-        min,
-        max,
-    };
-    let mut nodes = HashMap::<Key, Node>::new();
-    let mut unvisited = HashSet::<Key>::new();
-    let mut distances = HashMap::<Key, usize>::new();
-
-    for (row_number, row) in input.iter().enumerate() {
-        for (column_number, column) in row.chars().enumerate() {
-            let weight = column.to_digit(10).unwrap() as usize;
-
-            for lateral in [true, false] {
-                nodes.insert(
-                    (row_number, column_number, lateral),
-                    Node {
-                        weight,
-                        edges: get_edges(row_number, column_number, !lateral, limits),
-                    },
-                );
-
-                unvisited.insert((row_number, column_number, lateral));
-
-                if row_number == 0 && column_number == 0 {
-                    distances.insert((row_number, column_number, lateral), 0);
-                } else {
-                    distances.insert((row_number, column_number, lateral), usize::MAX);
-                }
+            if length >= min {
+                successors.push(((current, !vertical), cost));
             }
         }
     }
 
-    while !unvisited.is_empty() {
-        let temp_unvisited = unvisited.clone();
-        let current = temp_unvisited
-            .iter()
-            .min_by_key(|&key| distances.get(key).unwrap_or(&usize::MAX))
-            .expect("should have a min");
-        let distance = distances[current];
-
-        if current == &(limits.x, limits.y, true) || current == &(limits.x, limits.y, false) {
-            break;
-        }
-
-        if distance == usize::MAX {
-            break;
-        }
-
-        let new_distances = nodes[current]
-            .clone()
-            .edges
-            .par_iter()
-            .map(|edges| {
-                let mut tentative_distance = distance;
-                let mut new_distances = HashMap::<Key, usize>::new();
-
-                for (i, edge) in edges.iter().enumerate() {
-                    tentative_distance += nodes[edge].weight;
-
-                    if i + 1 >= limits.min && tentative_distance < distances[edge] {
-                        new_distances.insert(*edge, tentative_distance);
-                    }
-                }
-
-                new_distances
-            })
-            .flatten()
-            .collect::<HashMap<_, _>>();
-
-        for (key, distance) in new_distances {
-            distances.insert(key, distance);
-        }
+    successors
+}
 
-        unvisited.remove(current);
+fn step_towards(from: usize, to: usize) -> usize {
+    match from.cmp(&to) {
+        Ordering::Less => from + 1,
+        Ordering::Equal => from,
+        Ordering::Greater => from - 1,
     }
-
-    let result = [
-        distances[&(limits.x, limits.y, true)],
-        distances[&(limits.x, limits.y, false)],
-    ]
-    .iter()
-    .map(|&distance| distance.to_owned())
-    .collect::<Vec<usize>>();
-    let result = result.iter().min().expect("should have a min");
-
-    result.to_owned()
 }
 
 #[cfg(test)]
@@ -192,11 +141,69 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 102);
+        assert_eq!(part1(get_fixture()), Ok(102.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 94);
+        assert_eq!(part2(get_fixture()), Ok(94.into()));
+        assert_eq!(
+            part2(
+                "111111111111
+                999999999991
+                999999999991
+                999999999991
+                999999999991"
+                    .split('\n')
+                    .map(|s| s.trim().to_string())
+                    .collect()
+            ),
+            Ok(71.into())
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render1(get_fixture(), &RenderOptions::default()),
+            Ok(r"2>>34^>>>1323
+                 32v>>>35v5623
+                 32552456v>>54
+                 3446585845v52
+                 4546657867v>6
+                 14385987984v4
+                 44578769877v6
+                 36378779796v>
+                 465496798688v
+                 456467998645v
+                 12246868655<v
+                 25465488877v5
+                 43226746555v>"
+                .split('\n')
+                .map(|s| s.trim())
+                .collect::<Vec<_>>()
+                .join("\n"))
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let fixture = get_fixture();
+        let path = dijkstra(fixture.clone(), 1, 3).expect("should have a path");
+        let heat = path
+            .nodes
+            .iter()
+            .skip(1)
+            .map(|(row, column)| fixture[*row].as_bytes()[*column] - b'0')
+            .map(usize::from)
+            .sum::<usize>();
+
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(12, 12)));
+        assert!(path
+            .nodes
+            .windows(2)
+            .all(|pair| pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1) == 1));
+        assert_eq!(heat, path.cost);
     }
 }
@@ -1,21 +1,16 @@
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::error::{parse_capture, ParseError, SolveError};
+use crate::geometry::{Direction, Point};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 #[derive(Debug, Clone)]
 struct Instruction {
-    direction: char,
+    direction: Direction,
     length: isize,
 }
 
-#[derive(Debug)]
-struct Node {
-    start: (isize, isize),
-    end: (isize, isize),
-    toggle: bool,
-}
-
-pub fn part1(input: Vec<String>) -> usize {
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
     static DIG_REGEX: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r"(?P<direction>U|D|R|L) (?P<length>\d+) \(#(?P<color>[a-f0-9]{6})\)")
             .expect("should be a valid regex")
@@ -23,28 +18,24 @@ pub fn part1(input: Vec<String>) -> usize {
 
     let mut instructions = Vec::new();
 
-    for line in input {
+    for (row, line) in input.iter().enumerate() {
         let captures = DIG_REGEX
             .captures(line.as_str())
-            .expect("should be able to capture");
-        let length = &captures["length"]
-            .parse::<isize>()
-            .expect("should be a valid number");
-        let direction = &captures["direction"]
+            .ok_or_else(|| ParseError::new(row, 0, "<direction> <length> (#<color>)"))?;
+        let length = parse_capture::<isize>(&captures, "length", row)?;
+        let direction = captures["direction"]
             .chars()
             .next()
-            .expect("should be a valid char");
+            .and_then(Direction::parse)
+            .expect("should be a valid direction");
 
-        instructions.push(Instruction {
-            direction: *direction,
-            length: *length,
-        });
+        instructions.push(Instruction { direction, length });
     }
 
-    get_size(instructions)
+    Ok(get_size(instructions)?.into())
 }
 
-pub fn part2(input: Vec<String>) -> usize {
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
     static DIG_REGEX: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r"(U|D|R|L) (\d+) \(#(?P<length>[a-f0-9]{5})(?P<direction>\d{1})\)")
             .expect("should be a valid regex")
@@ -52,162 +43,57 @@ pub fn part2(input: Vec<String>) -> usize {
 
     let mut instructions = Vec::new();
 
-    for line in input {
+    for (row, line) in input.iter().enumerate() {
         let captures = DIG_REGEX
             .captures(line.as_str())
-            .expect("should be able to capture");
+            .ok_or_else(|| ParseError::new(row, 0, "<direction> <length> (#<color>)"))?;
         let length =
             isize::from_str_radix(&captures["length"], 16).expect("should be a valid number");
-        let direction = &captures["direction"]
-            .chars()
-            .next()
-            .expect("should be a valid char");
-        let direction = match direction {
-            '0' => 'R',
-            '1' => 'D',
-            '2' => 'L',
-            '3' => 'U',
-            _ => panic!("should be a valid direction"),
+        let direction = captures.name("direction").expect("should have a direction");
+        let direction = match direction.as_str() {
+            "0" => Direction::Right,
+            "1" => Direction::Down,
+            "2" => Direction::Left,
+            "3" => Direction::Up,
+            _ => {
+                return Err(
+                    ParseError::new(row, direction.start(), "a direction from 0 to 3").into(),
+                )
+            }
         };
 
         instructions.push(Instruction { direction, length });
     }
 
-    get_size(instructions)
+    Ok(get_size(instructions)?.into())
 }
 
-fn get_size(instructions: Vec<Instruction>) -> usize {
-    let mut nodes: Vec<Node> = vec![];
-    let mut row = 0;
-    let mut col = 0;
-    let mut previous = (row, col);
-    let mut direction_sequence = vec![];
-    let mut previous_direction = instructions
-        .last()
-        .expect("should have a last instruction")
-        .direction;
-    let mut min_row = 0;
-    let mut max_row = 0;
-    let mut min_col = 0;
-    let mut max_col = 0;
-
-    for instruction in instructions {
-        match instruction.direction {
-            'U' => {
-                row -= instruction.length;
-
-                if direction_sequence
-                    .last()
-                    .expect("should have a last direction")
-                    == &'D'
-                    && ['L', 'R'].contains(&previous_direction)
-                {
-                    let last = nodes.last_mut().expect("should have a last node");
-
-                    last.toggle = false;
-                }
-            }
-            'D' => {
-                row += instruction.length;
-
-                if direction_sequence
-                    .last()
-                    .expect("should have a last direction")
-                    == &'U'
-                    && ['L', 'R'].contains(&previous_direction)
-                {
-                    let last = nodes.last_mut().expect("should have a last node");
-
-                    last.toggle = false;
-                }
-            }
-            'L' => {
-                col -= instruction.length;
-            }
-            'R' => {
-                col += instruction.length;
-            }
-            _ => panic!("should be a valid direction"),
-        }
-
-        if previous.1 > col {
-            nodes.push(Node {
-                start: (row, col),
-                end: previous,
-                toggle: true,
-            });
-        } else {
-            nodes.push(Node {
-                start: previous,
-                end: (row, col),
-                toggle: true,
-            });
-        }
-
-        previous = (row, col);
-
-        direction_sequence.push(previous_direction);
-        previous_direction = instruction.direction;
-
-        if row < min_row {
-            min_row = row;
-        }
-
-        if row > max_row {
-            max_row = row;
-        }
-
-        if col < min_col {
-            min_col = col;
-        }
-
-        if col > max_col {
-            max_col = col;
-        }
+// The shoelace formula gives the area enclosed by the middle of the trench, and Pick's theorem
+// adds the outer half of the trench itself.
+fn get_size(instructions: Vec<Instruction>) -> Result<u128, SolveError> {
+    if instructions.is_empty() {
+        return Err(ParseError::new(0, 0, "a dig instruction").into());
     }
 
-    let mut result = 0;
-
-    for row in min_row..=max_row {
-        let mut bounds = nodes
-            .iter()
-            .filter(|node| {
-                (node.start.0 == row && node.end.0 == row)
-                    || (node.start.1 == node.end.1
-                        && (node.start.0 > row && node.end.0 < row
-                            || node.start.0 < row && node.end.0 > row))
-            })
-            .map(|node| (node.toggle, node.start.1, node.end.1))
-            .sorted_by_key(|node| node.1)
-            .collect_vec();
-
-        if bounds.is_empty() {
-            bounds.push((false, min_col, max_col));
-        }
-
-        let first_bound = bounds.first().expect("should have a first bound");
-        let mut inside = first_bound.0;
-
-        result += first_bound.2 - first_bound.1 + 1;
-
-        let mut prev_end = first_bound.2 + 1;
+    let mut current = Point::default();
+    let mut area = 0_i128;
+    let mut perimeter = 0_u128;
 
-        for bound in bounds.iter().skip(1) {
-            if inside {
-                result += bound.1 - prev_end;
-            }
-
-            result += bound.2 - bound.1 + 1;
+    for instruction in instructions {
+        let next = current.step(instruction.direction, instruction.length);
 
-            prev_end = bound.2 + 1;
+        area += current.x as i128 * next.y as i128 - next.x as i128 * current.y as i128;
+        perimeter += current.get_manhattan_distance(next) as u128;
+        current = next;
+    }
 
-            if bound.0 {
-                inside = !inside;
-            }
-        }
+    if current != Point::default() {
+        return Err(SolveError::Unsolvable(
+            "the trench should end where it started".to_string(),
+        ));
     }
 
-    result.unsigned_abs()
+    Ok(area.unsigned_abs() / 2 + perimeter / 2 + 1)
 }
 
 #[cfg(test)]
@@ -236,11 +122,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 62);
+        assert_eq!(part1(get_fixture()), Ok(62.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 952_408_144_115);
+        assert_eq!(part2(get_fixture()), Ok(952_408_144_115_u64.into()));
     }
 }
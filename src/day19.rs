@@ -1,186 +1,232 @@
-use core::panic;
+use crate::answer::Answer;
+use crate::error::{parse_capture, parse_number, ParseError, SolveError};
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeInclusive;
 
-pub fn part1(input: Vec<String>) -> usize {
-    let mut workflows = WorkflowMap::new();
-    let mut items = Vec::<Item>::new();
-    let mut is_workflow = true;
+pub type Ratings = BTreeMap<String, u128>;
 
-    for line in input {
-        if line.is_empty() {
-            is_workflow = false;
-            continue;
-        }
+pub type Domains = BTreeMap<String, RangeInclusive<u128>>;
 
-        if is_workflow {
-            let workflow = Workflow::parse(line);
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let split = input
+        .iter()
+        .position(|line| line.is_empty())
+        .unwrap_or(input.len());
+    let workflows = Workflows::parse(&input[..split])?;
+    let mut items = vec![];
 
-            workflows.insert(workflow.key.clone(), workflow);
-        } else {
-            items.push(Item::parse(&line));
-        }
+    for (row, line) in input.iter().enumerate().skip(split + 1) {
+        items.push(parse_ratings(line, row)?);
     }
 
     let result = items
         .par_iter()
-        .map(|item| {
-            let mut current_key = "in";
-            let mut visited = HashSet::new();
-
-            visited.insert(current_key);
-
-            loop {
-                let workflow = workflows
-                    .get(current_key)
-                    .expect("should be a valid workflow");
-                let last_rule = &workflow.rules.last().expect("should have a final rule");
-                let mut next_key = if last_rule.condition.is_none() {
-                    last_rule.destination.as_str()
-                } else {
-                    panic!("should be a default rule")
-                };
-
-                for rule in &workflow.rules {
-                    match rule.condition.clone() {
-                        None => continue,
-                        Some(Condition::GreaterThan(attribute, value)) => {
-                            let matching = item.get(attribute.to_owned()) > value;
-
-                            if matching {
-                                next_key = rule.destination.as_str();
-                                break;
-                            }
-                        }
-                        Some(Condition::LessThan(attribute, value)) => {
-                            let matching = item.get(attribute.to_owned()) < value;
-
-                            if matching {
-                                next_key = rule.destination.as_str();
-                                break;
-                            }
-                        }
-                    }
-                }
+        .map(|ratings| {
+            if workflows.accepts(ratings)? {
+                Ok(ratings.values().sum())
+            } else {
+                Ok(0)
+            }
+        })
+        .sum::<Result<u128, SolveError>>()?;
 
-                if visited.contains(&next_key) {
-                    break;
-                }
+    Ok(result.into())
+}
 
-                if next_key == "R" {
-                    return 0;
-                }
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let split = input
+        .iter()
+        .position(|line| line.is_empty())
+        .unwrap_or(input.len());
+    let workflows = Workflows::parse(&input[..split])?;
+    let domains = ["x", "m", "a", "s"]
+        .into_iter()
+        .map(|attribute| (attribute.to_string(), 1..=4000))
+        .collect();
+
+    Ok(workflows.count_accepted(&domains)?.into())
+}
 
-                if next_key == "A" {
-                    return item.sum();
-                }
+fn parse_ratings(line: &str, row: usize) -> Result<Ratings, ParseError> {
+    static RATING_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?P<attribute>[a-zA-Z_]+)=(?P<value>\d+)").expect("should be a valid regex")
+    });
 
-                visited.insert(next_key);
+    let mut ratings = Ratings::new();
 
-                current_key = next_key;
-            }
+    for captures in RATING_REGEX.captures_iter(line) {
+        let value = parse_capture::<u128>(&captures, "value", row)?;
 
-            0
-        })
-        .sum::<u128>();
+        ratings.insert(captures["attribute"].to_string(), value);
+    }
 
-    result as usize
+    Ok(ratings)
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let mut workflows = WorkflowMap::new();
-    let mut destination_map = HashMap::new();
-    let mut accepted_list = vec![];
+type Key = String;
 
-    for line in input {
-        if line.is_empty() {
-            break;
-        }
+// Items start at the "in" workflow and move between workflows until one sends them to A or R.
+pub struct Workflows {
+    workflows: HashMap<Key, Workflow>,
+}
 
-        let workflow = Workflow::parse(line);
+impl Workflows {
+    pub fn parse(input: &[String]) -> Result<Self, ParseError> {
+        let mut workflows = HashMap::new();
 
-        for (i, rule) in (0..).zip(workflow.clone().rules) {
-            let desitination = rule.destination;
+        for (row, line) in input.iter().enumerate() {
+            let workflow = Workflow::parse(line, row)?;
 
-            if desitination == "A" {
-                accepted_list.push((workflow.key.clone(), i));
-            } else if desitination != "R" {
-                destination_map.insert(desitination.clone(), (workflow.key.clone(), i));
-            }
+            workflows.insert(workflow.key.clone(), workflow);
         }
 
-        workflows.insert(workflow.key.clone(), workflow.clone());
+        Ok(Self { workflows })
     }
 
-    let result = accepted_list
-        .par_iter()
-        .map(|(key, index)| {
-            let mut range_map = RangeMap::default();
-            let mut current_key = key.as_str();
-            let mut current_index = *index;
-            let mut visited = HashSet::new();
-
-            visited.insert(current_key);
-
-            loop {
-                let workflow = workflows
-                    .get(current_key)
-                    .expect("should be a valid workflow");
-
-                match workflow.rules[current_index].condition.clone() {
-                    Some(Condition::GreaterThan(attribute, value)) => {
-                        range_map.set_greater_than(&attribute, value + 1);
-                    }
-                    Some(Condition::LessThan(attribute, value)) => {
-                        range_map.set_less_than(&attribute, value - 1);
-                    }
-                    _ => (),
-                }
+    // An item that comes back to a workflow it has already been through is never accepted.
+    pub fn accepts(&self, ratings: &Ratings) -> Result<bool, SolveError> {
+        let mut key = "in";
+        let mut visited = vec![];
 
-                for i in (0..current_index.to_owned()).rev() {
-                    let rule = workflow.rules[i].clone();
-
-                    match rule.condition {
-                        Some(Condition::GreaterThan(attribute, value)) => {
-                            range_map.set_less_than(&attribute, value);
-                        }
-                        Some(Condition::LessThan(attribute, value)) => {
-                            range_map.set_greater_than(&attribute, value);
-                        }
-                        _ => (),
+        while !visited.contains(&key) {
+            visited.push(key);
+
+            let workflow = self.get_workflow(key)?;
+            let mut next = None;
+
+            for rule in &workflow.rules {
+                let matching = match &rule.condition {
+                    None => true,
+                    Some(condition) => {
+                        let value = ratings.get(&condition.attribute).ok_or_else(|| {
+                            SolveError::Unsolvable(format!("no {} rating", condition.attribute))
+                        })?;
+
+                        condition.operator.test(*value, condition.value)
                     }
-                }
+                };
 
-                if workflow.key == "in" {
+                if matching {
+                    next = Some(rule.destination.as_str());
                     break;
                 }
+            }
 
-                let next = destination_map
-                    .get(workflow.key.as_str())
-                    .expect("should have a valid next");
+            match next.expect("should end with a default rule") {
+                "A" => return Ok(true),
+                "R" => return Ok(false),
+                destination => key = destination,
+            }
+        }
 
-                current_key = next.0.as_str();
-                current_index = next.1;
+        Ok(false)
+    }
 
-                if visited.contains(&current_key) {
-                    break;
+    // Counts the combinations of ratings within the domains that end up accepted, by splitting
+    // the ranges at each rule and following every piece to where it goes.
+    pub fn count_accepted(&self, domains: &Domains) -> Result<u128, SolveError> {
+        self.count("in", domains.clone(), &mut vec![])
+    }
+
+    fn count<'a>(
+        &'a self,
+        key: &'a str,
+        domains: Domains,
+        path: &mut Vec<&'a str>,
+    ) -> Result<u128, SolveError> {
+        match key {
+            "A" => return get_combinations(&domains),
+            "R" => return Ok(0),
+            _ if path.contains(&key) => return Ok(0),
+            _ => (),
+        }
+
+        let workflow = self.get_workflow(key)?;
+        let mut pending = vec![domains];
+        let mut result = 0;
+
+        path.push(key);
+
+        for rule in &workflow.rules {
+            let Some(condition) = &rule.condition else {
+                for domains in pending.drain(..) {
+                    result += self.count(&rule.destination, domains, path)?;
                 }
 
-                visited.insert(current_key);
+                break;
+            };
+            let mut rest = vec![];
+
+            for domains in pending {
+                let range = domains.get(&condition.attribute).ok_or_else(|| {
+                    SolveError::Unsolvable(format!("no domain for {}", condition.attribute))
+                })?;
+
+                for piece in split_range(range, condition.value) {
+                    let matching = condition.operator.test(*piece.start(), condition.value);
+                    let mut domains = domains.clone();
+
+                    domains.insert(condition.attribute.clone(), piece);
+
+                    if matching {
+                        result += self.count(&rule.destination, domains, path)?;
+                    } else {
+                        rest.push(domains);
+                    }
+                }
             }
 
-            range_map.sum()
-        })
-        .sum::<u128>();
+            pending = rest;
+        }
+
+        path.pop();
+
+        Ok(result)
+    }
 
-    result as usize
+    fn get_workflow(&self, key: &str) -> Result<&Workflow, SolveError> {
+        self.workflows
+            .get(key)
+            .ok_or_else(|| SolveError::Unsolvable(format!("no workflow named {key}")))
+    }
 }
 
-type WorkflowMap = HashMap<Key, Workflow>;
+// An empty domain has no combinations, and a domain covering every u128 has more than fit.
+fn get_combinations(domains: &Domains) -> Result<u128, SolveError> {
+    if domains.values().any(|range| range.is_empty()) {
+        return Ok(0);
+    }
 
-type Key = String;
+    domains
+        .values()
+        .try_fold(1u128, |combinations, range| {
+            (range.end() - range.start())
+                .checked_add(1)
+                .and_then(|size| combinations.checked_mul(size))
+        })
+        .ok_or_else(|| {
+            SolveError::Unsolvable("too many accepted combinations to count".to_string())
+        })
+}
+
+// Every operator gives the same answer for all the values below the threshold, for the threshold
+// itself and for all the values above it, so those are the only pieces a rule needs.
+fn split_range(range: &RangeInclusive<u128>, value: u128) -> Vec<RangeInclusive<u128>> {
+    let (start, end) = (*range.start(), *range.end());
+
+    [
+        value.checked_sub(1).map(|below| start..=end.min(below)),
+        Some(start.max(value)..=end.min(value)),
+        value.checked_add(1).map(|above| start.max(above)..=end),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|piece| !piece.is_empty())
+    .collect()
+}
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 struct Workflow {
@@ -189,36 +235,78 @@ struct Workflow {
 }
 
 impl Workflow {
-    fn parse(line: String) -> Self {
+    fn parse(line: &str, row: usize) -> Result<Self, ParseError> {
         static WORKFLOW_REGEX: Lazy<Regex> = Lazy::new(|| {
-            Regex::new(r"(?P<key>[a-zA-Z]+)\{(?P<rules>.*?),(?P<default_destination>[a-zA-Z]+)\}")
-                .expect("should be a valid regex")
+            Regex::new(
+                r"^(?P<key>[a-zA-Z]+)\{(?:(?P<rules>.*),)?(?P<default_destination>[a-zA-Z]+)\}$",
+            )
+            .expect("should be a valid regex")
         });
 
         let captures = WORKFLOW_REGEX
-            .captures(line.as_str())
-            .expect("should be able to capture");
-        let mut rules = captures["rules"]
-            .split(',')
-            .map(Rule::parse_normal)
-            .collect::<Vec<Rule>>();
+            .captures(line)
+            .ok_or_else(|| ParseError::new(row, 0, "<name>{<rules>,<destination>}"))?;
+        let mut rules = Vec::new();
+
+        if let Some(rules_capture) = captures.name("rules") {
+            let mut offset = rules_capture.start();
+
+            for rule in rules_capture.as_str().split(',') {
+                rules.push(Rule::parse_normal(rule, row, offset)?);
+
+                offset += rule.len() + 1;
+            }
+        }
 
         rules.push(Rule {
             condition: None,
             destination: captures["default_destination"].to_string(),
         });
 
-        Self {
+        Ok(Self {
             key: captures["key"].to_string(),
             rules,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Operator {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Operator {
+    fn parse(operator: &str) -> Self {
+        match operator {
+            "<" => Self::Less,
+            "<=" => Self::LessOrEqual,
+            "==" => Self::Equal,
+            ">=" => Self::GreaterOrEqual,
+            ">" => Self::Greater,
+            _ => unreachable!("should be a valid operator"),
+        }
+    }
+
+    fn test(self, rating: u128, value: u128) -> bool {
+        match self {
+            Self::Less => rating < value,
+            Self::LessOrEqual => rating <= value,
+            Self::Equal => rating == value,
+            Self::GreaterOrEqual => rating >= value,
+            Self::Greater => rating > value,
         }
     }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
-enum Condition {
-    LessThan(Attribute, u128),
-    GreaterThan(Attribute, u128),
+struct Condition {
+    attribute: String,
+    operator: Operator,
+    value: u128,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -228,160 +316,28 @@ struct Rule {
 }
 
 impl Rule {
-    fn parse_normal(line: &str) -> Self {
+    fn parse_normal(line: &str, row: usize, column: usize) -> Result<Self, ParseError> {
         static RULE_REGEX: Lazy<Regex> = Lazy::new(|| {
             Regex::new(
-                r"(?P<attribute>[xmas])(?P<operator>[<>])(?P<value>\d+):(?P<destination>[a-zA-Z]+)",
+                r"^(?P<attribute>[a-zA-Z_]+)(?P<operator><=|>=|==|<|>)(?P<value>\d+):(?P<destination>[a-zA-Z]+)$",
             )
             .expect("should be a valid regex")
         });
 
-        let captures = RULE_REGEX
-            .captures(line)
-            .expect("should be able to capture");
-        let attribute = Attribute::parse(captures["attribute"].to_string());
-        let operator = captures["operator"].to_string();
-        let value = captures["value"]
-            .parse::<u128>()
-            .expect("should be a valid number");
-
-        Self {
-            destination: captures["destination"].to_string(),
-            condition: match operator.as_str() {
-                ">" => Some(Condition::GreaterThan(attribute, value)),
-                "<" => Some(Condition::LessThan(attribute, value)),
-                _ => panic!("should be a valid operator"),
-            },
-        }
-    }
-}
-
-#[derive(PartialEq, Eq, Debug, Hash, Clone)]
-enum Attribute {
-    ExtremelyCool,
-    Musical,
-    Aerodynamic,
-    Shiny,
-}
-
-impl Attribute {
-    fn parse(letter: String) -> Self {
-        match letter.as_str() {
-            "x" => Self::ExtremelyCool,
-            "m" => Self::Musical,
-            "a" => Self::Aerodynamic,
-            "s" => Self::Shiny,
-            _ => panic!("should be a valid attribute"),
-        }
-    }
-}
-
-#[derive(Debug, Default)]
-struct Item {
-    x: u128,
-    m: u128,
-    a: u128,
-    s: u128,
-}
-
-impl Item {
-    fn get(&self, attribute: Attribute) -> u128 {
-        match attribute {
-            Attribute::ExtremelyCool => self.x,
-            Attribute::Musical => self.m,
-            Attribute::Aerodynamic => self.a,
-            Attribute::Shiny => self.s,
-        }
-    }
-
-    fn set(&mut self, attribute: Attribute, value: u128) {
-        match attribute {
-            Attribute::ExtremelyCool => self.x = value,
-            Attribute::Musical => self.m = value,
-            Attribute::Aerodynamic => self.a = value,
-            Attribute::Shiny => self.s = value,
-        }
-    }
-
-    fn sum(&self) -> u128 {
-        self.x + self.m + self.a + self.s
-    }
-
-    fn parse(line: &str) -> Self {
-        static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| {
-            Regex::new(r"(?P<attribute>[xmas])=(?P<value>\d+)").expect("should be a valid regex")
-        });
-
-        let mut item = Self::default();
-
-        for (_, [attribute, value]) in ATTRIBUTE_REGEX.captures_iter(line).map(|c| c.extract()) {
-            let attribute = Attribute::parse(attribute.to_string());
-            let value = value.parse::<u128>().expect("should be a valid number");
-
-            item.set(attribute, value);
-        }
-
-        item
-    }
-}
-
-#[derive(Debug)]
-struct RangeMap {
-    x: (u128, u128),
-    m: (u128, u128),
-    a: (u128, u128),
-    s: (u128, u128),
-}
-
-impl Default for RangeMap {
-    fn default() -> Self {
-        Self {
-            x: (1, 4000),
-            m: (1, 4000),
-            a: (1, 4000),
-            s: (1, 4000),
-        }
-    }
-}
-
-impl RangeMap {
-    fn get_mut(&mut self, attribute: &Attribute) -> &mut (u128, u128) {
-        match attribute {
-            Attribute::ExtremelyCool => &mut self.x,
-            Attribute::Musical => &mut self.m,
-            Attribute::Aerodynamic => &mut self.a,
-            Attribute::Shiny => &mut self.s,
-        }
-    }
-
-    fn set_greater_than(&mut self, attribute: &Attribute, value: u128) {
-        let range = self.get_mut(attribute);
-
-        if value > range.0 {
-            range.0 = value
-        }
-    }
-
-    fn set_less_than(&mut self, attribute: &Attribute, value: u128) {
-        let range = self.get_mut(attribute);
-
-        if value < range.1 {
-            range.1 = value
-        }
-    }
-
-    fn sum(&self) -> u128 {
-        let mut result = 1;
+        let captures = RULE_REGEX.captures(line).ok_or_else(|| {
+            ParseError::new(row, column, "<attribute><operator><value>:<destination>")
+        })?;
+        let value = captures.name("value").expect("should have a value");
+        let value = parse_number::<u128>(value.as_str(), row, column + value.start())?;
 
-        for range in [self.x, self.m, self.a, self.s] {
-            if range.1 < range.0 {
-                result *= 0;
-            } else {
-                result *= range.1 - range.0 + 1;
-            }
-        }
-
-        result
+        Ok(Self {
+            destination: captures["destination"].to_string(),
+            condition: Some(Condition {
+                attribute: captures["attribute"].to_string(),
+                operator: Operator::parse(&captures["operator"]),
+                value,
+            }),
+        })
     }
 }
 
@@ -414,11 +370,92 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 19114);
+        assert_eq!(part1(get_fixture()), Ok(19114.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(get_fixture()), 167409079868000);
+        assert_eq!(part2(get_fixture()), Ok(167_409_079_868_000_u128.into()));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let mut input = get_fixture();
+
+        input[3] = "rfg{s<537:gd,x?2440:R,A}".to_string();
+
+        assert_eq!(
+            part1(input.clone()),
+            Err(ParseError::new(3, 13, "<attribute><operator><value>:<destination>").into())
+        );
+
+        input[3] = "rfg".to_string();
+
+        assert_eq!(
+            part2(input),
+            Err(ParseError::new(3, 0, "<name>{<rules>,<destination>}").into())
+        );
+    }
+
+    #[test]
+    fn test_workflows() {
+        let workflows = Workflows::parse(&[
+            "in{speed>=10:fast,colour==3:A,R}".to_string(),
+            "fast{speed<=20:A,weight<5:A,R}".to_string(),
+        ])
+        .expect("should parse");
+        let ratings = |speed, colour, weight| {
+            Ratings::from([
+                ("speed".to_string(), speed),
+                ("colour".to_string(), colour),
+                ("weight".to_string(), weight),
+            ])
+        };
+        let mut domains = Domains::from([
+            ("speed".to_string(), 0..=99),
+            ("colour".to_string(), 0..=9),
+            ("weight".to_string(), 0..=9),
+        ]);
+
+        assert_eq!(workflows.accepts(&ratings(15, 0, 9)), Ok(true));
+        assert_eq!(workflows.accepts(&ratings(50, 3, 5)), Ok(false));
+        assert_eq!(workflows.accepts(&ratings(5, 3, 0)), Ok(true));
+        assert_eq!(workflows.accepts(&ratings(5, 4, 0)), Ok(false));
+        assert_eq!(workflows.count_accepted(&domains), Ok(5150));
+
+        let count_slowly = itertools::iproduct!(0..=99, 0..=9, 0..=9)
+            .filter(|(speed, colour, weight)| {
+                workflows.accepts(&ratings(*speed, *colour, *weight)) == Ok(true)
+            })
+            .count();
+
+        assert_eq!(count_slowly, 5150);
+
+        assert_eq!(
+            Workflows::parse(&["in{a<5:A,R}".to_string()])
+                .expect("should parse")
+                .count_accepted(&Domains::from([
+                    ("a".to_string(), 1..=10),
+                    ("b".to_string(), RangeInclusive::new(5, 1)),
+                ])),
+            Ok(0)
+        );
+        assert_eq!(
+            workflows.count_accepted(&Domains::from([
+                ("speed".to_string(), 0..=u128::MAX),
+                ("colour".to_string(), 0..=9),
+                ("weight".to_string(), 0..=9),
+            ])),
+            Err(SolveError::Unsolvable(
+                "too many accepted combinations to count".to_string()
+            ))
+        );
+
+        domains.remove("weight");
+
+        assert_eq!(
+            workflows.count_accepted(&domains),
+            Err(SolveError::Unsolvable("no domain for weight".to_string()))
+        );
     }
 }
@@ -1,14 +1,47 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use crate::registry::RenderOptions;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pulse {
+    pub src: String,
+    pub high: bool,
+    pub dst: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ModuleState {
+    FlipFlop(bool),
+    Conjunction(BTreeMap<String, bool>),
+    Broadcaster,
+}
+
+// How many low and high pulses were sent, either by one module or by the whole network.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counter {
+    pub lows: usize,
+    pub highs: usize,
+}
+
+pub struct Network {
+    modules: HashMap<String, Box<dyn Module>>,
+    counters: HashMap<String, Counter>,
+    presses: usize,
+}
 
 trait Module {
     fn get_destinations(&self) -> Vec<String>;
     fn pulse(&mut self, high: bool, src: String) -> Vec<(bool, String)>;
     fn register(&mut self, _src: String) {}
+    fn get_state(&self) -> ModuleState;
+    fn clone_box(&self) -> Box<dyn Module>;
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct FlipFlop {
     destinations: Vec<String>,
     on: bool,
@@ -31,9 +64,17 @@ impl Module for FlipFlop {
             .map(|s| (self.on, s.to_string()))
             .collect()
     }
+
+    fn get_state(&self) -> ModuleState {
+        ModuleState::FlipFlop(self.on)
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct Conjunction {
     destinations: Vec<String>,
     inputs: HashMap<String, bool>,
@@ -66,9 +107,22 @@ impl Module for Conjunction {
     fn register(&mut self, src: String) {
         self.inputs.insert(src, false);
     }
+
+    fn get_state(&self) -> ModuleState {
+        ModuleState::Conjunction(
+            self.inputs
+                .iter()
+                .map(|(name, high)| (name.to_string(), *high))
+                .collect(),
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct Broadcaster {
     destinations: Vec<String>,
 }
@@ -84,19 +138,27 @@ impl Module for Broadcaster {
             .map(|k| (high, k.to_owned()))
             .collect()
     }
+
+    fn get_state(&self) -> ModuleState {
+        ModuleState::Broadcaster
+    }
+
+    fn clone_box(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
-fn parse_modules(input: Vec<String>) -> HashMap<String, Box<dyn Module>> {
+fn parse_modules(input: Vec<String>) -> Result<HashMap<String, Box<dyn Module>>, ParseError> {
     static MODULE_REGEX: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r"(?P<type>[%&])?(?P<name>[a-z]+) -> (?P<destinations>.*)")
             .expect("should be a valid regex")
     });
     let mut modules: HashMap<String, Box<dyn Module>> = HashMap::new();
 
-    for line in input {
+    for (row, line) in input.iter().enumerate() {
         let captures = MODULE_REGEX
             .captures(line.as_str())
-            .expect("should be able to capture");
+            .ok_or_else(|| ParseError::new(row, 0, "<module> -> <destinations>"))?;
         let destinations = captures["destinations"]
             .split(", ")
             .map(|s| s.to_string())
@@ -132,112 +194,348 @@ fn parse_modules(input: Vec<String>) -> HashMap<String, Box<dyn Module>> {
         }
     }
 
-    modules
+    Ok(modules)
 }
 
-pub fn part1(input: Vec<String>) -> usize {
-    let mut modules = parse_modules(input);
-    let mut highs = 0;
-    let mut lows = 0;
+impl Network {
+    pub fn parse(input: Vec<String>) -> Result<Self, SolveError> {
+        let modules = parse_modules(input)?;
 
-    for _i in 0..1000 {
-        lows += 1;
+        if !modules.contains_key("broadcaster") {
+            return Err(SolveError::Unsolvable("no broadcaster module".to_string()));
+        }
 
-        let broadcaster = modules.get("broadcaster").expect("should have broadcaster");
-        let mut queue = broadcaster
-            .get_destinations()
-            .iter()
-            .map(|s| (false, s.to_string(), "button".to_string()))
-            .collect::<Vec<_>>();
+        Ok(Self {
+            modules,
+            counters: HashMap::new(),
+            presses: 0,
+        })
+    }
+
+    // Sends a low pulse to the broadcaster and returns every pulse in the order it was sent, once
+    // the network has settled.
+    pub fn press_button(&mut self) -> Vec<Pulse> {
+        let mut log = vec![];
+        let mut queue = VecDeque::from([Pulse {
+            src: "button".to_string(),
+            high: false,
+            dst: "broadcaster".to_string(),
+        }]);
+
+        self.presses += 1;
+
+        while let Some(pulse) = queue.pop_front() {
+            if let Some(counter) = self.counters.get_mut(&pulse.src) {
+                if pulse.high {
+                    counter.highs += 1;
+                } else {
+                    counter.lows += 1;
+                }
+            }
 
-        loop {
-            if queue.is_empty() {
-                break;
+            if let Some(module) = self.modules.get_mut(&pulse.dst) {
+                queue.extend(
+                    module
+                        .pulse(pulse.high, pulse.src.to_string())
+                        .into_iter()
+                        .map(|(high, dst)| Pulse {
+                            src: pulse.dst.to_string(),
+                            high,
+                            dst,
+                        }),
+                );
             }
 
-            let mut new_queue = vec![];
+            log.push(pulse);
+        }
 
-            for (high, dest, src) in queue {
-                if high {
-                    highs += 1;
+        log
+    }
+
+    // Presses the button on a copy of the network until it repeats a state, then extrapolates the
+    // rest from the cycle, so huge press counts only cost one run around the cycle. The network
+    // itself, its presses and its counters are left as they were.
+    pub fn count_pulses(&self, presses: usize) -> Counter {
+        let mut network = self.clone();
+        let mut seen = HashMap::new();
+        let mut counts = vec![];
+
+        while counts.len() < presses {
+            if let Some(start) = seen.insert(network.get_state(), counts.len()) {
+                let cycle = &counts[start..];
+                let (repeats, rest) = (
+                    (presses - start) / cycle.len(),
+                    (presses - start) % cycle.len(),
+                );
+
+                return [
+                    sum_counters(&counts[..start]),
+                    sum_counters(cycle).times(repeats),
+                    sum_counters(&cycle[..rest]),
+                ]
+                .into_iter()
+                .fold(Counter::default(), Counter::add);
+            }
+
+            let mut count = Counter::default();
+
+            for pulse in network.press_button() {
+                if pulse.high {
+                    count.highs += 1;
                 } else {
-                    lows += 1;
+                    count.lows += 1;
                 }
+            }
+
+            counts.push(count);
+        }
+
+        sum_counters(&counts)
+    }
 
-                if let Some(module) = modules.get_mut(&dest) {
-                    new_queue.extend(
-                        module
-                            .pulse(high, src)
-                            .iter()
-                            .map(|(h, n)| (h.to_owned(), n.to_owned(), dest.to_owned()))
-                            .collect::<Vec<_>>(),
-                    );
+    pub fn get_presses(&self) -> usize {
+        self.presses
+    }
+
+    pub fn add_counter(&mut self, name: &str) {
+        self.counters.entry(name.to_string()).or_default();
+    }
+
+    pub fn get_counter(&self, name: &str) -> Option<Counter> {
+        self.counters.get(name).copied()
+    }
+
+    pub fn get_state(&self) -> BTreeMap<String, ModuleState> {
+        self.modules
+            .iter()
+            .map(|(name, module)| (name.to_string(), module.get_state()))
+            .collect()
+    }
+
+    pub fn get_sources(&self, name: &str) -> Vec<String> {
+        let mut sources = self
+            .modules
+            .iter()
+            .filter(|(_, module)| module.get_destinations().iter().any(|d| d == name))
+            .map(|(src, _)| src.to_string())
+            .collect::<Vec<_>>();
+
+        sources.sort();
+
+        sources
+    }
+
+    // Graphviz source with a shape for each kind of module. When coloured, flip-flops that are on
+    // and conjunctions that remember a high pulse from every input are filled green.
+    pub fn to_dot(&self, coloured: bool) -> String {
+        let mut names = self.modules.keys().collect::<Vec<_>>();
+        let mut outputs = vec![];
+        let mut dot = "digraph modules {\n".to_string();
+
+        names.sort();
+
+        for name in &names {
+            let module = &self.modules[*name];
+            let state = module.get_state();
+            let (shape, on) = match &state {
+                ModuleState::FlipFlop(on) => ("box", Some(*on)),
+                ModuleState::Conjunction(inputs) => {
+                    ("invtrapezium", Some(inputs.values().all(|h| *h)))
+                }
+                ModuleState::Broadcaster => ("doubleoctagon", None),
+            };
+            let fill = match (coloured, on) {
+                (true, Some(true)) => ", style=filled, fillcolor=palegreen",
+                (true, Some(false)) => ", style=filled, fillcolor=lightgray",
+                _ => "",
+            };
+
+            writeln!(dot, "  {name} [shape={shape}{fill}];").expect("should write to a string");
+
+            for destination in module.get_destinations() {
+                if !self.modules.contains_key(&destination) && !outputs.contains(&destination) {
+                    outputs.push(destination);
                 }
             }
+        }
+
+        outputs.sort();
 
-            queue = new_queue;
+        for output in outputs {
+            writeln!(dot, "  {output} [shape=plaintext];").expect("should write to a string");
         }
+
+        for name in names {
+            for destination in self.modules[name].get_destinations() {
+                writeln!(dot, "  {name} -> {destination};").expect("should write to a string");
+            }
+        }
+
+        dot.push('}');
+
+        dot
     }
+}
 
-    highs * lows
+impl Clone for Network {
+    fn clone(&self) -> Self {
+        Self {
+            modules: self
+                .modules
+                .iter()
+                .map(|(name, module)| (name.to_string(), module.clone_box()))
+                .collect(),
+            counters: self.counters.clone(),
+            presses: self.presses,
+        }
+    }
 }
 
-pub fn part2(input: Vec<String>) -> usize {
-    let mut modules = parse_modules(input);
-    let mut results = HashMap::new();
+impl Counter {
+    fn add(self, other: Self) -> Self {
+        Self {
+            lows: self.lows + other.lows,
+            highs: self.highs + other.highs,
+        }
+    }
 
-    'o: for i in 1.. {
-        let broadcaster = modules.get("broadcaster").expect("should have broadcaster");
-        let mut queue = broadcaster
-            .get_destinations()
-            .iter()
-            .map(|s| (false, s.to_string(), "button".to_string()))
-            .collect::<Vec<_>>();
-        let (penultimate, _) = modules
-            .iter()
-            .find(|(_, module)| module.get_destinations().contains(&"rx".to_string()))
-            .expect("should have penultimate");
-        let penultimate_src_len = modules
-            .iter()
-            .filter(|(_, module)| module.get_destinations().contains(&penultimate.to_string()))
-            .collect::<Vec<_>>()
-            .len();
+    fn times(self, n: usize) -> Self {
+        Self {
+            lows: self.lows * n,
+            highs: self.highs * n,
+        }
+    }
+}
+
+fn sum_counters(counters: &[Counter]) -> Counter {
+    counters
+        .iter()
+        .copied()
+        .fold(Counter::default(), Counter::add)
+}
 
-        loop {
-            if queue.is_empty() {
-                break;
+impl fmt::Display for Pulse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = if self.high { "high" } else { "low" };
+
+        write!(f, "{} -{level}-> {}", self.src, self.dst)
+    }
+}
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let count = Network::parse(input)?.count_pulses(1000);
+
+    Ok((count.lows * count.highs).into())
+}
+
+// Draws the network as it is after --presses button presses, or uncoloured without it.
+pub fn render(input: Vec<String>, options: &RenderOptions) -> Result<String, SolveError> {
+    let mut network = Network::parse(input)?;
+
+    for _ in 0..options.presses.unwrap_or(0) {
+        network.press_button();
+    }
+
+    Ok(network.to_dot(options.presses.is_some()))
+}
+
+// rx gets a low pulse once every input of the conjunction feeding it has sent a high pulse on the
+// same press. Each input is the end of an independent counter, so the gap between its first two
+// high pulses is the length of its cycle and the answer is where all the cycles line up. That only
+// holds if each input first fires at a multiple of its cycle, so anything else is rejected.
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let mut network = Network::parse(input)?;
+    let penultimate = get_penultimate(&network)?;
+    let sources = network.get_sources(&penultimate);
+    let mut firings: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut seen = HashMap::new();
+
+    while sources
+        .iter()
+        .any(|source| firings.get(source).map_or(0, Vec::len) < 2)
+    {
+        // Once the network is back in a state it has been in, every press from here on repeats
+        // one before it, so an input that fired inside the loop fires again one loop later and
+        // any other input that hasn't fired twice never will.
+        if let Some(start) = seen.insert(network.get_state(), network.get_presses()) {
+            let length = network.get_presses() - start;
+            let silent = sources
+                .iter()
+                .filter(|source| !firings.contains_key(*source))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if !silent.is_empty() {
+                return Err(SolveError::Unsolvable(format!(
+                    "no high pulse reaches {penultimate} from {}",
+                    silent.join(", ")
+                )));
             }
 
-            if let Some((_h, _dest, src)) = queue.iter().find(|(h, dest, _src)| *h && dest == "zh")
-            {
-                if !results.contains_key(src) {
-                    results.insert(src.to_string(), i);
+            for source in &sources {
+                let presses = firings.entry(source.to_string()).or_default();
+
+                match presses.as_slice() {
+                    [first] if *first > start => presses.push(first + length),
+                    [_] => {
+                        return Err(SolveError::Unsolvable(format!(
+                            "only one high pulse reaches {penultimate} from {source}"
+                        )))
+                    }
+                    _ => {}
                 }
             }
 
-            if results.len() == penultimate_src_len {
-                break 'o;
-            }
+            break;
+        }
+
+        for pulse in network.press_button() {
+            if pulse.high && pulse.dst == penultimate {
+                let presses = firings.entry(pulse.src).or_default();
 
-            let mut new_queue = vec![];
-
-            for (high, dest, src) in queue {
-                if let Some(module) = modules.get_mut(&dest) {
-                    new_queue.extend(
-                        module
-                            .pulse(high, src)
-                            .iter()
-                            .map(|(h, n)| (h.to_owned(), n.to_owned(), dest.to_owned()))
-                            .collect::<Vec<_>>(),
-                    );
+                if presses.len() < 2 && presses.last() != Some(&network.get_presses()) {
+                    presses.push(network.get_presses());
                 }
             }
+        }
+    }
+
+    let mut answer = 1;
+
+    for source in &sources {
+        let (first, second) = (firings[source][0], firings[source][1]);
+        let cycle = second - first;
 
-            queue = new_queue;
+        if first % cycle != 0 {
+            return Err(SolveError::Unsolvable(format!(
+                "{source} first sends a high pulse to {penultimate} on press {first} but repeats \
+                 every {cycle}"
+            )));
         }
+
+        answer = num::integer::lcm(answer, cycle);
     }
 
-    results.values().product()
+    Ok(answer.into())
+}
+
+fn get_penultimate(network: &Network) -> Result<String, SolveError> {
+    let feeders = network.get_sources("rx");
+    let state = network.get_state();
+
+    match feeders.as_slice() {
+        [] => Err(SolveError::Unsolvable("no module sends to rx".to_string())),
+        [name] if matches!(state.get(name), Some(ModuleState::Conjunction(_))) => {
+            Ok(name.to_string())
+        }
+        [name] => Err(SolveError::Unsolvable(format!(
+            "{name} sends to rx but isn't a conjunction"
+        ))),
+        _ => Err(SolveError::Unsolvable(format!(
+            "{} modules send to rx instead of one conjunction",
+            feeders.len()
+        ))),
+    }
 }
 
 #[cfg(test)]
@@ -257,6 +555,230 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(get_fixture()), 32000000);
+        assert_eq!(part1(get_fixture()), Ok(32000000.into()));
+    }
+
+    #[test]
+    fn test_part2() {
+        // Each counter resets itself once its conjunction fires, so ia fires every 3 presses and
+        // ib every 5.
+        let input = r"broadcaster -> aa, ba
+                      %aa -> ab, na
+                      %ab -> na
+                      &na -> ia, aa
+                      &ia -> hub
+                      %ba -> bb, nb
+                      %bb -> bc
+                      %bc -> nb
+                      &nb -> ib, ba, bb
+                      &ib -> hub
+                      &hub -> rx";
+        // Without the resets the first counter fires on press 3 and then every 4 presses.
+        let counters = r"broadcaster -> aa, ba
+                      %aa -> ab, na
+                      %ab -> na
+                      &na -> ia
+                      &ia -> hub
+                      %ba -> bb, nb
+                      %bb -> bc, nb
+                      %bc -> bd, nb
+                      %bd -> nb
+                      &nb -> ib
+                      &ib -> hub
+                      &hub -> rx";
+        let parse = |input: &str| {
+            input
+                .split('\n')
+                .map(|s| s.trim().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(part2(parse(input)), Ok(15.into()));
+        assert_eq!(
+            part2(parse(counters)),
+            Err(SolveError::Unsolvable(
+                "ia first sends a high pulse to hub on press 3 but repeats every 4".to_string()
+            ))
+        );
+        assert_eq!(
+            part2(get_fixture()),
+            Err(SolveError::Unsolvable("no module sends to rx".to_string()))
+        );
+        assert_eq!(
+            part2(parse("broadcaster -> a\n%a -> rx")),
+            Err(SolveError::Unsolvable(
+                "a sends to rx but isn't a conjunction".to_string()
+            ))
+        );
+        assert_eq!(
+            part2(parse("broadcaster -> a, b\n&a -> rx\n&b -> rx")),
+            Err(SolveError::Unsolvable(
+                "2 modules send to rx instead of one conjunction".to_string()
+            ))
+        );
+        assert_eq!(
+            part2(parse("broadcaster -> a\n&a -> hub\n&b -> hub\n&hub -> rx")),
+            Err(SolveError::Unsolvable(
+                "no high pulse reaches hub from b".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_press_button() {
+        let mut network = Network::parse(
+            r"broadcaster -> a
+              %a -> inv, con
+              &inv -> b
+              %b -> con
+              &con -> output"
+                .split('\n')
+                .map(|s| s.trim().to_string())
+                .collect(),
+        )
+        .expect("should parse");
+
+        network.add_counter("con");
+
+        assert_eq!(
+            network
+                .press_button()
+                .iter()
+                .map(|pulse| pulse.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -high-> inv",
+                "a -high-> con",
+                "inv -low-> b",
+                "con -high-> output",
+                "b -high-> con",
+                "con -low-> output",
+            ]
+        );
+        assert_eq!(
+            network.get_counter("con"),
+            Some(Counter { lows: 1, highs: 1 })
+        );
+        assert_eq!(network.get_counter("b"), None);
+
+        let state = network.get_state();
+
+        assert_eq!(state["a"], ModuleState::FlipFlop(true));
+        assert_eq!(
+            state["con"],
+            ModuleState::Conjunction(BTreeMap::from([
+                ("a".to_string(), true),
+                ("b".to_string(), true),
+            ]))
+        );
+
+        network.press_button();
+
+        assert_eq!(network.get_presses(), 2);
+        assert_eq!(network.get_state()["a"], ModuleState::FlipFlop(false));
+    }
+
+    #[test]
+    fn test_render() {
+        let input = r"broadcaster -> a
+                      %a -> inv, con
+                      &inv -> b
+                      %b -> con
+                      &con -> output"
+            .split('\n')
+            .map(|s| s.trim().to_string())
+            .collect::<Vec<_>>();
+        let dot = render(input.clone(), &RenderOptions::default()).expect("should render");
+
+        assert_eq!(
+            dot.lines().collect::<Vec<_>>(),
+            vec![
+                "digraph modules {",
+                "  a [shape=box];",
+                "  b [shape=box];",
+                "  broadcaster [shape=doubleoctagon];",
+                "  con [shape=invtrapezium];",
+                "  inv [shape=invtrapezium];",
+                "  output [shape=plaintext];",
+                "  a -> inv;",
+                "  a -> con;",
+                "  b -> con;",
+                "  broadcaster -> a;",
+                "  con -> output;",
+                "  inv -> b;",
+                "}",
+            ]
+        );
+
+        let dot =
+            render(input.clone(), &RenderOptions { presses: Some(0) }).expect("should render");
+
+        assert!(dot.contains("  a [shape=box, style=filled, fillcolor=lightgray];"));
+        assert!(dot.contains("  broadcaster [shape=doubleoctagon];"));
+
+        let dot = render(input, &RenderOptions { presses: Some(1) }).expect("should render");
+
+        assert!(dot.contains("  a [shape=box, style=filled, fillcolor=palegreen];"));
+        assert!(dot.contains("  con [shape=invtrapezium, style=filled, fillcolor=palegreen];"));
+    }
+
+    #[test]
+    fn test_count_pulses() {
+        let input = r"broadcaster -> a
+                      %a -> inv, con
+                      &inv -> b
+                      %b -> con
+                      &con -> output"
+            .split('\n')
+            .map(|s| s.trim().to_string())
+            .collect::<Vec<_>>();
+
+        for presses in 0..20 {
+            let mut network = Network::parse(input.clone()).expect("should parse");
+            let counted = network.count_pulses(presses);
+            let mut expected = Counter::default();
+
+            for pulse in (0..presses).flat_map(|_| network.press_button()) {
+                if pulse.high {
+                    expected.highs += 1;
+                } else {
+                    expected.lows += 1;
+                }
+            }
+
+            assert_eq!(counted, expected);
+        }
+
+        let mut network = Network::parse(input).expect("should parse");
+
+        network.add_counter("con");
+        network.press_button();
+
+        let state = network.get_state();
+
+        assert_eq!(
+            network.count_pulses(1_000_000_000_000),
+            Counter {
+                lows: 4_250_000_000_000,
+                highs: 2_750_000_000_000,
+            }
+        );
+        assert_eq!(network.get_presses(), 1);
+        assert_eq!(network.get_state(), state);
+        assert_eq!(
+            network.get_counter("con"),
+            Some(Counter { lows: 1, highs: 1 })
+        );
+        assert_eq!(
+            Network::parse(get_fixture())
+                .expect("should parse")
+                .count_pulses(1_000_000_000_000),
+            Counter {
+                lows: 8_000_000_000_000,
+                highs: 4_000_000_000_000,
+            }
+        );
     }
 }
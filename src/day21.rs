@@ -1,59 +1,158 @@
-use std::collections::{HashMap, HashSet};
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use std::collections::HashSet;
 
-pub fn part1(input: Vec<String>) -> usize {
-    get_steps(input, 64)
+const MAX_SAMPLES: usize = 32;
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_steps(input, 64)?.into())
 }
 
-pub fn part2(_input: Vec<String>) -> usize {
-    0
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_infinite_steps(input, 26_501_365)?.into())
 }
 
-pub fn get_steps(input: Vec<String>, steps: usize) -> usize {
-    let mut grid = HashMap::<(isize, isize), bool>::new();
-    let mut plots = HashSet::<(isize, isize)>::new();
+pub fn get_steps(input: Vec<String>, steps: usize) -> Result<usize, SolveError> {
+    Ok(Plots::parse(input, false)?
+        .nth(steps)
+        .expect("should always have a next count"))
+}
 
-    for (y, line) in input.iter().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let is_plot = match c {
-                '.' => true,
-                'S' => {
-                    plots.insert((x as isize, y as isize));
+// Like get_steps, but with the garden repeating forever in every direction.
+pub fn get_tiled_steps(input: Vec<String>, steps: usize) -> Result<usize, SolveError> {
+    Ok(Plots::parse(input, true)?
+        .nth(steps)
+        .expect("should always have a next count"))
+}
 
-                    true
-                }
-                '#' => false,
-                _ => panic!("Invalid character"),
-            };
+pub fn get_infinite_steps(input: Vec<String>, steps: usize) -> Result<u128, SolveError> {
+    let plots = Plots::parse(input, true)?;
+    let period = num::integer::lcm(plots.grid.get_height(), plots.grid.get_width());
+    let remainder = steps % period;
+    let target = steps / period;
+    let limit = remainder + (MAX_SAMPLES - 1) * period;
+    let mut samples = Vec::new();
+
+    for (step, count) in plots.enumerate().take(limit + 1) {
+        if step % period != remainder {
+            continue;
+        }
 
-            grid.insert((x as isize, y as isize), is_plot);
+        if step == steps {
+            return Ok(count as u128);
+        }
+
+        samples.push(count);
+
+        if samples.len() < 6 {
+            continue;
+        }
+
+        let differences = samples
+            .windows(3)
+            .rev()
+            .take(3)
+            .map(|w| w[2] as i128 - 2 * w[1] as i128 + w[0] as i128)
+            .collect::<Vec<_>>();
+
+        if differences.iter().all(|d| *d == differences[0]) {
+            let last = samples.len() - 1;
+            let remaining = (target - last) as i128;
+            let first_difference = samples[last] as i128 - samples[last - 1] as i128;
+            let result = samples[last] as i128
+                + remaining * first_difference
+                + differences[0] * remaining * (remaining + 1) / 2;
+
+            return u128::try_from(result).map_err(|_| {
+                SolveError::Unsolvable("the plot count should not shrink".to_string())
+            });
         }
     }
 
-    for _i in 0..steps {
-        let mut new_plots = HashSet::<(isize, isize)>::new();
+    Err(SolveError::Unsolvable(format!(
+        "the plot counts didn't settle into quadratic growth within {MAX_SAMPLES} samples"
+    )))
+}
 
-        for (x, y) in plots.clone() {
-            if *grid.get(&(x - 1, y)).unwrap_or(&false) {
-                new_plots.insert((x - 1, y));
-            }
+struct Plots {
+    grid: Grid<bool>,
+    tiled: bool,
+    seen: HashSet<Point>,
+    frontier: Vec<Point>,
+    totals: [usize; 2],
+    step: usize,
+}
 
-            if *grid.get(&(x + 1, y)).unwrap_or(&false) {
-                new_plots.insert((x + 1, y));
-            }
+impl Plots {
+    fn parse(input: Vec<String>, tiled: bool) -> Result<Self, SolveError> {
+        let grid = Grid::parse(&input, "., # or S", |c| {
+            ['.', '#', 'S'].contains(&c).then_some(c)
+        })?;
+        let starts = grid
+            .iter()
+            .filter(|(_, cell)| **cell == 'S')
+            .map(|((row, column), _)| Point::new(column as isize, row as isize))
+            .collect::<Vec<_>>();
+
+        if starts.len() != 1 {
+            return Err(SolveError::Unsolvable(
+                "there should be exactly one start position".to_string(),
+            ));
+        }
 
-            if *grid.get(&(x, y - 1)).unwrap_or(&false) {
-                new_plots.insert((x, y - 1));
-            }
+        Ok(Self {
+            grid: grid.map(|cell| *cell != '#'),
+            tiled,
+            seen: starts.iter().cloned().collect(),
+            frontier: starts,
+            totals: [0, 0],
+            step: 0,
+        })
+    }
 
-            if *grid.get(&(x, y + 1)).unwrap_or(&false) {
-                new_plots.insert((x, y + 1));
-            }
+    fn is_plot(&self, point: Point) -> bool {
+        if !self.tiled {
+            return usize::try_from(point.y)
+                .ok()
+                .zip(usize::try_from(point.x).ok())
+                .and_then(|coords| self.grid.get(coords))
+                .is_some_and(|plot| *plot);
         }
 
-        plots = new_plots;
+        let row = point.y.rem_euclid(self.grid.get_height() as isize) as usize;
+        let column = point.x.rem_euclid(self.grid.get_width() as isize) as usize;
+
+        self.grid[(row, column)]
     }
+}
+
+impl Iterator for Plots {
+    type Item = usize;
+
+    // A plot reached in n steps can be reached again in n + 2 steps by stepping away and back, so
+    // the plots reachable in exactly n steps are those first reached at a step of the same parity.
+    fn next(&mut self) -> Option<Self::Item> {
+        let parity = self.step % 2;
 
-    plots.len()
+        self.totals[parity] += self.frontier.len();
+
+        let mut new_frontier = Vec::new();
+
+        for point in &self.frontier {
+            for next in point.get_neighbors() {
+                if self.is_plot(next) && self.seen.insert(next) {
+                    new_frontier.push(next);
+                }
+            }
+        }
+
+        self.frontier = new_frontier;
+        self.step += 1;
+
+        Some(self.totals[parity])
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +178,44 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(get_steps(get_fixture(), 6), 16);
+        assert_eq!(get_steps(get_fixture(), 6), Ok(16));
+    }
+
+    #[test]
+    fn test_get_steps() {
+        let input = ["S.#", "..."].map(|s| s.to_string()).to_vec();
+
+        assert_eq!(get_steps(input.clone(), 3), Ok(3));
+        assert_eq!(get_steps(input.clone(), 100), Ok(2));
+        assert_eq!(get_tiled_steps(input, 3), Ok(11));
+    }
+
+    #[test]
+    fn test_get_tiled_steps() {
+        assert_eq!(get_tiled_steps(get_fixture(), 6), Ok(16));
+        assert_eq!(get_tiled_steps(get_fixture(), 10), Ok(50));
+        assert_eq!(get_tiled_steps(get_fixture(), 50), Ok(1594));
+        assert_eq!(get_tiled_steps(get_fixture(), 100), Ok(6536));
+    }
+
+    #[test]
+    fn test_get_infinite_steps() {
+        assert_eq!(get_infinite_steps(get_fixture(), 6), Ok(16));
+        assert_eq!(get_infinite_steps(get_fixture(), 100), Ok(6536));
+        assert_eq!(get_infinite_steps(get_fixture(), 500), Ok(167004));
+        assert_eq!(get_infinite_steps(get_fixture(), 1000), Ok(668697));
+        assert_eq!(get_infinite_steps(get_fixture(), 5000), Ok(16733044));
+    }
+
+    #[test]
+    fn test_get_infinite_steps_non_square() {
+        let input = [".....", "..S..", ".#..."].map(|s| s.to_string()).to_vec();
+
+        for steps in [100, 201] {
+            assert_eq!(
+                get_infinite_steps(input.clone(), steps),
+                get_tiled_steps(input.clone(), steps).map(|count| count as u128)
+            );
+        }
     }
 }
@@ -0,0 +1,158 @@
+use crate::answer::Answer;
+use crate::error::{parse_numbers, ParseError, SolveError};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+type Coords = (usize, usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Brick {
+    start: Coords,
+    end: Coords,
+}
+
+#[derive(Debug, Default)]
+struct Stack {
+    supports: Vec<HashSet<usize>>,
+    supported_by: Vec<HashSet<usize>>,
+}
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let stack = settle(parse_bricks(input)?);
+
+    let result = stack
+        .supports
+        .iter()
+        .filter(|above| {
+            above
+                .iter()
+                .all(|brick| stack.supported_by[*brick].len() > 1)
+        })
+        .count();
+
+    Ok(result.into())
+}
+
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let stack = settle(parse_bricks(input)?);
+
+    let result = (0..stack.supports.len())
+        .into_par_iter()
+        .map(|brick| get_falling(&stack, brick))
+        .sum::<usize>();
+
+    Ok(result.into())
+}
+
+fn get_falling(stack: &Stack, brick: usize) -> usize {
+    let mut fallen = HashSet::from([brick]);
+    let mut queue = VecDeque::from([brick]);
+
+    while let Some(current) = queue.pop_front() {
+        for above in &stack.supports[current] {
+            if !fallen.contains(above) && stack.supported_by[*above].is_subset(&fallen) {
+                fallen.insert(*above);
+                queue.push_back(*above);
+            }
+        }
+    }
+
+    fallen.len() - 1
+}
+
+fn settle(mut bricks: Vec<Brick>) -> Stack {
+    bricks.sort_by_key(|brick| brick.start.2);
+
+    let mut stack = Stack {
+        supports: vec![HashSet::new(); bricks.len()],
+        supported_by: vec![HashSet::new(); bricks.len()],
+    };
+    let mut heights = HashMap::<(usize, usize), (usize, usize)>::new();
+
+    for (i, brick) in bricks.iter().enumerate() {
+        let cells = (brick.start.0..=brick.end.0)
+            .cartesian_product(brick.start.1..=brick.end.1)
+            .collect::<Vec<_>>();
+        let floor = cells
+            .iter()
+            .filter_map(|cell| heights.get(cell))
+            .map(|(height, _)| *height)
+            .max()
+            .unwrap_or(0);
+
+        for cell in &cells {
+            if let Some((height, below)) = heights.get(cell) {
+                if *height == floor {
+                    stack.supports[*below].insert(i);
+                    stack.supported_by[i].insert(*below);
+                }
+            }
+        }
+
+        let top = floor + brick.end.2 - brick.start.2 + 1;
+
+        for cell in cells {
+            heights.insert(cell, (top, i));
+        }
+    }
+
+    stack
+}
+
+fn parse_bricks(input: Vec<String>) -> Result<Vec<Brick>, ParseError> {
+    let mut bricks = vec![];
+
+    for (row, line) in input.iter().enumerate() {
+        let (start, end) = line
+            .split_once('~')
+            .ok_or_else(|| ParseError::new(row, 0, "<x>,<y>,<z>~<x>,<y>,<z>"))?;
+        let (start, end) = [(start, 0), (end, start.len() + 1)]
+            .into_iter()
+            .map(|(coords, column)| {
+                parse_numbers::<usize>(coords, ',', row, column)?
+                    .into_iter()
+                    .collect_tuple::<Coords>()
+                    .ok_or_else(|| ParseError::new(row, column, "<x>,<y>,<z>"))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .collect_tuple()
+            .expect("should be a tuple of two coordinates");
+
+        bricks.push(Brick {
+            start: (start.0.min(end.0), start.1.min(end.1), start.2.min(end.2)),
+            end: (start.0.max(end.0), start.1.max(end.1), start.2.max(end.2)),
+        });
+    }
+
+    Ok(bricks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Vec<String> {
+        "1,0,1~1,2,1
+        0,0,2~2,0,2
+        0,2,3~2,2,3
+        0,0,4~0,2,4
+        2,0,5~2,2,5
+        0,1,6~2,1,6
+        1,1,8~1,1,9"
+            .split('\n')
+            .map(|s| s.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(get_fixture()), Ok(5.into()));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(get_fixture()), Ok(7.into()));
+    }
+}
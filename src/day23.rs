@@ -0,0 +1,263 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+type Coords = (usize, usize);
+type EdgeList = Vec<Vec<(usize, usize)>>;
+
+const SPLIT_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    node: usize,
+    visited: u64,
+    distance: usize,
+}
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_longest(input, true)?.into())
+}
+
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_longest(input, false)?.into())
+}
+
+fn get_longest(input: Vec<String>, slippery: bool) -> Result<usize, SolveError> {
+    for (row, line) in input.iter().enumerate() {
+        if let Some((column, _)) = line
+            .chars()
+            .find_position(|c| !['#', '.', '^', 'v', '<', '>'].contains(c))
+        {
+            return Err(ParseError::new(row, column, "a path, forest or slope").into());
+        }
+    }
+
+    let grid = input
+        .iter()
+        .map(|line| line.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let (edges, start, end) = get_edges(&grid, slippery)?;
+    let (last, last_distance) = get_last_junction(&edges, end);
+    let mut states = vec![State {
+        node: start,
+        visited: 1 << start,
+        distance: 0,
+    }];
+
+    for _ in 0..SPLIT_DEPTH {
+        states = states
+            .iter()
+            .flat_map(|state| {
+                if state.node == last {
+                    return vec![*state];
+                }
+
+                edges[state.node]
+                    .iter()
+                    .filter(|(next, _)| state.visited & (1 << next) == 0)
+                    .map(|(next, distance)| State {
+                        node: *next,
+                        visited: state.visited | (1 << next),
+                        distance: state.distance + distance,
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    let longest = states
+        .par_iter()
+        .filter_map(|state| search(&edges, last, *state))
+        .max()
+        .ok_or_else(|| SolveError::Unsolvable("no path to the end".to_string()))?;
+
+    Ok(longest + last_distance)
+}
+
+fn search(edges: &EdgeList, last: usize, state: State) -> Option<usize> {
+    if state.node == last {
+        return Some(state.distance);
+    }
+
+    edges[state.node]
+        .iter()
+        .filter(|(next, _)| state.visited & (1 << next) == 0)
+        .filter_map(|(next, distance)| {
+            search(
+                edges,
+                last,
+                State {
+                    node: *next,
+                    visited: state.visited | (1 << next),
+                    distance: state.distance + distance,
+                },
+            )
+        })
+        .max()
+}
+
+// The end has a single way in, so any path that reaches that junction has to go straight to the
+// end rather than wander off and block itself.
+fn get_last_junction(edges: &EdgeList, end: usize) -> (usize, usize) {
+    let entries = edges
+        .iter()
+        .enumerate()
+        .filter_map(|(node, edges)| {
+            edges
+                .iter()
+                .find(|(next, _)| *next == end)
+                .map(|(_, distance)| (node, *distance))
+        })
+        .collect::<Vec<_>>();
+
+    match entries.as_slice() {
+        [entry] => *entry,
+        _ => (end, 0),
+    }
+}
+
+fn get_edges(grid: &[Vec<char>], slippery: bool) -> Result<(EdgeList, usize, usize), SolveError> {
+    let last_row = grid.len().max(1) - 1;
+    let start = (
+        0,
+        grid.first()
+            .and_then(|cells| cells.iter().position(|c| *c == '.'))
+            .ok_or_else(|| ParseError::new(0, 0, "a path in the first row"))?,
+    );
+    let end = (
+        last_row,
+        grid[last_row]
+            .iter()
+            .position(|c| *c == '.')
+            .ok_or_else(|| ParseError::new(last_row, 0, "a path in the last row"))?,
+    );
+    let mut junctions = vec![start, end];
+
+    for (row, cells) in grid.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            if *cell != '#' && get_neighbors(grid, (row, column), false).len() > 2 {
+                junctions.push((row, column));
+            }
+        }
+    }
+
+    if junctions.len() > 64 {
+        return Err(SolveError::Unsolvable(
+            "the trails should have at most 64 junctions".to_string(),
+        ));
+    }
+
+    let indexes = junctions
+        .iter()
+        .enumerate()
+        .map(|(i, coords)| (*coords, i))
+        .collect::<HashMap<_, _>>();
+    let edges = junctions
+        .iter()
+        .map(|junction| {
+            get_neighbors(grid, *junction, slippery)
+                .into_iter()
+                .filter_map(|next| {
+                    let mut previous = *junction;
+                    let mut current = next;
+                    let mut distance = 1;
+
+                    while !indexes.contains_key(&current) {
+                        let next = get_neighbors(grid, current, slippery)
+                            .into_iter()
+                            .find(|coords| *coords != previous)?;
+
+                        previous = current;
+                        current = next;
+                        distance += 1;
+                    }
+
+                    Some((indexes[&current], distance))
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok((edges, 0, 1))
+}
+
+fn get_neighbors(grid: &[Vec<char>], (row, column): Coords, slippery: bool) -> Vec<Coords> {
+    let mut neighbors = vec![];
+    let candidates = [
+        (row.checked_sub(1), Some(column), '^'),
+        (Some(row + 1), Some(column), 'v'),
+        (Some(row), column.checked_sub(1), '<'),
+        (Some(row), Some(column + 1), '>'),
+    ];
+
+    for (next_row, next_column, slope) in candidates {
+        let (Some(next_row), Some(next_column)) = (next_row, next_column) else {
+            continue;
+        };
+        let Some(next) = grid.get(next_row).and_then(|cells| cells.get(next_column)) else {
+            continue;
+        };
+
+        if *next == '#' {
+            continue;
+        }
+
+        if slippery
+            && ([grid[row][column], *next]
+                .iter()
+                .any(|c| ['^', 'v', '<', '>'].contains(c) && *c != slope))
+        {
+            continue;
+        }
+
+        neighbors.push((next_row, next_column));
+    }
+
+    neighbors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Vec<String> {
+        r"#.#####################
+          #.......#########...###
+          #######.#########.#.###
+          ###.....#.>.>.###.#.###
+          ###v#####.#v#.###.#.###
+          ###.>...#.#.#.....#...#
+          ###v###.#.#.#########.#
+          ###...#.#.#.......#...#
+          #####.#.#.#######.#.###
+          #.....#.#.#.......#...#
+          #.#####.#.#.#########v#
+          #.#...#...#...###...>.#
+          #.#.#v#######v###.###v#
+          #...#.>.#...>.>.#.###.#
+          #####v#.#.###v#.#.###.#
+          #.....#...#...#.#.#...#
+          #.#########.###.#.#.###
+          #...###...#...#...#.###
+          ###.###.#.###v#####v###
+          #...#...#.#.>.>.#.>.###
+          #.###.###.#.###.#.#v###
+          #.....###...###...#...#
+          #####################.#"
+            .split('\n')
+            .map(|s| s.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(get_fixture()), Ok(94.into()));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(get_fixture()), Ok(154.into()));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::answer::Answer;
+use crate::error::{parse_numbers, ParseError, SolveError};
+use itertools::Itertools;
+use num::{BigInt, BigRational, Zero};
+
+type Vector = (i128, i128, i128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hailstone {
+    position: Vector,
+    velocity: Vector,
+}
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    Ok(get_intersections(input, 200_000_000_000_000, 400_000_000_000_000)?.into())
+}
+
+pub fn part2(input: Vec<String>) -> Result<Answer, SolveError> {
+    let (x, y, z) = get_rock(input)?;
+
+    Ok((x + y + z).into())
+}
+
+pub fn get_intersections(input: Vec<String>, min: i128, max: i128) -> Result<usize, SolveError> {
+    let hailstones = parse_hailstones(input)?;
+
+    Ok(hailstones
+        .iter()
+        .tuple_combinations()
+        .filter(|(a, b)| {
+            let denominator = a.velocity.0 * b.velocity.1 - a.velocity.1 * b.velocity.0;
+
+            if denominator == 0 {
+                return false;
+            }
+
+            let dx = b.position.0 - a.position.0;
+            let dy = b.position.1 - a.position.1;
+            let t = dx * b.velocity.1 - dy * b.velocity.0;
+            let s = dx * a.velocity.1 - dy * a.velocity.0;
+
+            if t.signum() * denominator.signum() < 0 || s.signum() * denominator.signum() < 0 {
+                return false;
+            }
+
+            // Scale everything by the denominator rather than dividing, so that the comparison
+            // against the test area stays exact.
+            let sign = denominator.signum();
+            let x = (a.position.0 * denominator + t * a.velocity.0) * sign;
+            let y = (a.position.1 * denominator + t * a.velocity.1) * sign;
+            let min = min * denominator.abs();
+            let max = max * denominator.abs();
+
+            x >= min && x <= max && y >= min && y <= max
+        })
+        .count())
+}
+
+pub fn get_rock(input: Vec<String>) -> Result<Vector, SolveError> {
+    let hailstones = parse_hailstones(input)?;
+    let solution = hailstones
+        .iter()
+        .tuple_combinations()
+        .find_map(|(a, b, c)| {
+            let mut rows = get_equations(a, b);
+
+            rows.extend(get_equations(a, c));
+
+            solve(rows)
+        })
+        .ok_or_else(|| SolveError::Unsolvable("no position for the rock".to_string()))?;
+    let (x, y, z) = solution
+        .iter()
+        .take(3)
+        .map(|value| {
+            if !value.is_integer() {
+                return None;
+            }
+
+            i128::try_from(value.to_integer()).ok()
+        })
+        .collect::<Option<Vec<_>>>()
+        .and_then(|values| values.into_iter().collect_tuple())
+        .ok_or_else(|| SolveError::Unsolvable("no integer position for the rock".to_string()))?;
+
+    Ok((x, y, z))
+}
+
+// For a rock at P with velocity V and a hailstone at p with velocity v to collide, (P - p) and
+// (V - v) must be parallel, so (P - p) × (V - v) = 0. Subtracting that equation for two
+// hailstones cancels the P × V term and leaves three equations linear in P and V.
+fn get_equations(a: &Hailstone, b: &Hailstone) -> Vec<Vec<BigRational>> {
+    let u = sub(b.position, a.position);
+    let w = sub(b.velocity, a.velocity);
+    let rhs = sub(cross(b.position, b.velocity), cross(a.position, a.velocity));
+
+    [
+        [0, w.2, -w.1, 0, -u.2, u.1, rhs.0],
+        [-w.2, 0, w.0, u.2, 0, -u.0, rhs.1],
+        [w.1, -w.0, 0, -u.1, u.0, 0, rhs.2],
+    ]
+    .iter()
+    .map(|row| {
+        row.iter()
+            .map(|value| BigRational::from_integer(BigInt::from(*value)))
+            .collect()
+    })
+    .collect()
+}
+
+fn solve(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let size = rows.len();
+
+    for column in 0..size {
+        let pivot = (column..size).find(|row| !rows[*row][column].is_zero())?;
+
+        rows.swap(column, pivot);
+
+        for row in 0..size {
+            if row == column || rows[row][column].is_zero() {
+                continue;
+            }
+
+            let factor = &rows[row][column] / &rows[column][column];
+            let pivot_row = rows[column].clone();
+
+            for (value, pivot_value) in rows[row].iter_mut().zip(pivot_row).skip(column) {
+                *value -= pivot_value * &factor;
+            }
+        }
+    }
+
+    Some(
+        rows.iter()
+            .enumerate()
+            .map(|(i, row)| &row[size] / &row[i])
+            .collect(),
+    )
+}
+
+fn sub(a: Vector, b: Vector) -> Vector {
+    (a.0 - b.0, a.1 - b.1, a.2 - b.2)
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    (
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+
+fn parse_hailstones(input: Vec<String>) -> Result<Vec<Hailstone>, ParseError> {
+    let mut hailstones = vec![];
+
+    for (row, line) in input.iter().enumerate() {
+        let (position, velocity) = line
+            .split_once('@')
+            .ok_or_else(|| ParseError::new(row, 0, "<position> @ <velocity>"))?;
+        let (position, velocity) = [(position, 0), (velocity, position.len() + 1)]
+            .into_iter()
+            .map(|(vector, column)| {
+                parse_numbers::<i128>(vector, ',', row, column)?
+                    .into_iter()
+                    .collect_tuple::<Vector>()
+                    .ok_or_else(|| ParseError::new(row, column, "<x>, <y>, <z>"))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .collect_tuple()
+            .expect("should be a tuple of position and velocity");
+
+        hailstones.push(Hailstone { position, velocity });
+    }
+
+    Ok(hailstones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Vec<String> {
+        "19, 13, 30 @ -2,  1, -2
+        18, 19, 22 @ -1, -1, -2
+        20, 25, 34 @ -2, -2, -4
+        12, 31, 28 @ -1, -2, -1
+        20, 19, 15 @  1, -5, -3"
+            .split('\n')
+            .map(|s| s.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_intersections() {
+        assert_eq!(get_intersections(get_fixture(), 7, 27), Ok(2));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(get_rock(get_fixture()), Ok((24, 13, 10)));
+        assert_eq!(part2(get_fixture()), Ok(47.into()));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::answer::Answer;
+use crate::error::{ParseError, SolveError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const CUT_SIZE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub edges: Vec<(String, String)>,
+    pub sizes: (usize, usize),
+}
+
+struct Graph {
+    names: Vec<String>,
+    edges: Vec<Vec<usize>>,
+}
+
+pub fn part1(input: Vec<String>) -> Result<Answer, SolveError> {
+    let cut = get_cut(input)?;
+
+    Ok((cut.sizes.0 * cut.sizes.1).into())
+}
+
+pub fn get_cut(input: Vec<String>) -> Result<Cut, SolveError> {
+    let graph = parse_graph(input)?;
+
+    (1..graph.names.len())
+        .find_map(|sink| graph.get_cut(0, sink))
+        .ok_or_else(|| SolveError::Unsolvable("no cut of three wires".to_string()))
+}
+
+impl Graph {
+    // Every wire has a capacity of one, so the max flow between two components on opposite sides
+    // of the cut is exactly the cut size. Once the flow is saturated, whatever is still reachable
+    // from the source is one side of the cut.
+    fn get_cut(&self, source: usize, sink: usize) -> Option<Cut> {
+        let mut flows = HashMap::<(usize, usize), isize>::new();
+
+        for _ in 0..CUT_SIZE {
+            let path = self.get_augmenting_path(source, sink, &flows)?;
+
+            for (a, b) in path {
+                *flows.entry((a, b)).or_insert(0) += 1;
+                *flows.entry((b, a)).or_insert(0) -= 1;
+            }
+        }
+
+        if self.get_augmenting_path(source, sink, &flows).is_some() {
+            return None;
+        }
+
+        let reachable = self.get_reachable(source, &flows);
+        let mut edges = vec![];
+
+        for a in &reachable {
+            for b in &self.edges[*a] {
+                if !reachable.contains(b) {
+                    let mut names = [self.names[*a].clone(), self.names[*b].clone()];
+
+                    names.sort();
+
+                    let [a, b] = names;
+
+                    edges.push((a, b));
+                }
+            }
+        }
+
+        edges.sort();
+
+        Some(Cut {
+            edges,
+            sizes: (reachable.len(), self.names.len() - reachable.len()),
+        })
+    }
+
+    fn get_augmenting_path(
+        &self,
+        source: usize,
+        sink: usize,
+        flows: &HashMap<(usize, usize), isize>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut previous = HashMap::from([(source, source)]);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(current) = queue.pop_front() {
+            if current == sink {
+                let mut path = vec![];
+                let mut node = sink;
+
+                while node != source {
+                    path.push((previous[&node], node));
+                    node = previous[&node];
+                }
+
+                return Some(path);
+            }
+
+            for next in &self.edges[current] {
+                if !previous.contains_key(next) && flows.get(&(current, *next)).unwrap_or(&0) < &1 {
+                    previous.insert(*next, current);
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn get_reachable(
+        &self,
+        source: usize,
+        flows: &HashMap<(usize, usize), isize>,
+    ) -> HashSet<usize> {
+        let mut reachable = HashSet::from([source]);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(current) = queue.pop_front() {
+            for next in &self.edges[current] {
+                if flows.get(&(current, *next)).unwrap_or(&0) < &1 && reachable.insert(*next) {
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+fn parse_graph(input: Vec<String>) -> Result<Graph, ParseError> {
+    let mut graph = Graph {
+        names: vec![],
+        edges: vec![],
+    };
+    let mut indexes = HashMap::<String, usize>::new();
+    let mut get_index = |graph: &mut Graph, name: &str| {
+        *indexes.entry(name.to_string()).or_insert_with(|| {
+            graph.names.push(name.to_string());
+            graph.edges.push(vec![]);
+
+            graph.names.len() - 1
+        })
+    };
+
+    for (row, line) in input.iter().enumerate() {
+        let (name, others) = line
+            .split_once(": ")
+            .ok_or_else(|| ParseError::new(row, 0, "<component>: <components>"))?;
+        let a = get_index(&mut graph, name);
+
+        for other in others.split(' ') {
+            let b = get_index(&mut graph, other);
+
+            graph.edges[a].push(b);
+            graph.edges[b].push(a);
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Vec<String> {
+        "jqt: rhn xhk nvd
+        rsh: frs pzl lsr
+        xhk: hfx
+        cmg: qnr nvd lhk bvb
+        rhn: xhk bvb hfx
+        bvb: xhk hfx
+        pzl: lsr hfx nvd
+        qnr: nvd
+        ntq: jqt hfx bvb xhk
+        nvd: lhk
+        lsr: lhk
+        rzs: qnr cmg lsr rsh
+        frs: qnr lhk lsr"
+            .split('\n')
+            .map(|s| s.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(get_fixture()), Ok(54.into()));
+    }
+
+    #[test]
+    fn test_get_cut() {
+        assert_eq!(
+            get_cut(get_fixture()).map(|cut| cut.edges),
+            Ok(vec![
+                ("bvb".to_string(), "cmg".to_string()),
+                ("hfx".to_string(), "pzl".to_string()),
+                ("jqt".to_string(), "nvd".to_string()),
+            ])
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use regex::Captures;
+use std::fmt;
+use std::str::FromStr;
+
+// Lines and columns are zero-based indexes into the input, and are only shown one-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    Parse(ParseError),
+    Unsolvable(String),
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: &str) -> Self {
+        Self {
+            line,
+            column,
+            expected: expected.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} at line {}, column {}",
+            self.expected,
+            self.line + 1,
+            self.column + 1
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "{error}"),
+            Self::Unsolvable(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl SolveError {
+    pub fn render(&self, input: &[String]) -> String {
+        let Self::Parse(error) = self else {
+            return format!("error: {self}");
+        };
+        let number = (error.line + 1).to_string();
+        let gutter = " ".repeat(number.len());
+        let line = input.get(error.line).map(|s| s.as_str()).unwrap_or("");
+
+        format!(
+            "error: {error}\n{gutter} |\n{number} | {line}\n{gutter} | {}^",
+            " ".repeat(line.chars().take(error.column).count())
+        )
+    }
+}
+
+pub fn parse_number<T: FromStr>(value: &str, line: usize, column: usize) -> Result<T, ParseError> {
+    let trimmed = value.trim_start();
+
+    trimmed
+        .trim_end()
+        .parse::<T>()
+        .map_err(|_| ParseError::new(line, column + value.len() - trimmed.len(), "a number"))
+}
+
+pub fn parse_numbers<T: FromStr>(
+    value: &str,
+    separator: char,
+    line: usize,
+    column: usize,
+) -> Result<Vec<T>, ParseError> {
+    let mut numbers = vec![];
+    let mut offset = column;
+
+    for part in value.split(separator) {
+        if !part.trim().is_empty() {
+            numbers.push(parse_number(part, line, offset)?);
+        }
+
+        offset += part.len() + separator.len_utf8();
+    }
+
+    Ok(numbers)
+}
+
+pub fn parse_capture<T: FromStr>(
+    captures: &Captures,
+    name: &str,
+    line: usize,
+) -> Result<T, ParseError> {
+    let capture = captures.name(name).expect("should have a capture group");
+
+    parse_number(capture.as_str(), line, capture.start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number::<usize>(" 42 ", 0, 3), Ok(42));
+        assert_eq!(
+            parse_number::<usize>("  x", 2, 3),
+            Err(ParseError::new(2, 5, "a number"))
+        );
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(
+            parse_numbers::<isize>("1  -2 3", ' ', 0, 0),
+            Ok(vec![1, -2, 3])
+        );
+        assert_eq!(
+            parse_numbers::<usize>("1, 2,x", ',', 4, 10),
+            Err(ParseError::new(4, 15, "a number"))
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let input = ["Game 1: 3 blue".to_string(), "Game x: 4 red".to_string()];
+        let error = SolveError::from(ParseError::new(1, 5, "a number"));
+
+        assert_eq!(error.to_string(), "expected a number at line 2, column 6");
+        assert_eq!(
+            error.render(&input),
+            [
+                "error: expected a number at line 2, column 6",
+                "  |",
+                "2 | Game x: 4 red",
+                "  |      ^",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            SolveError::Unsolvable("no start position".to_string()).render(&input),
+            "error: no start position"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use std::ops::{Add, Sub};
+
+// Screen coordinates, so y grows downwards and Up is a negative y.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    pub fn step(self, direction: Direction, distance: isize) -> Self {
+        let offset = direction.get_offset();
+
+        Self::new(self.x + offset.x * distance, self.y + offset.y * distance)
+    }
+
+    pub fn get_neighbors(self) -> [Self; 4] {
+        Direction::ALL.map(|direction| self + direction.get_offset())
+    }
+
+    pub fn get_manhattan_distance(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    // Accepts both the letters and the arrows that puzzles use for directions.
+    pub fn parse(c: char) -> Option<Self> {
+        match c {
+            'U' | '^' => Some(Self::Up),
+            'R' | '>' => Some(Self::Right),
+            'D' | 'v' => Some(Self::Down),
+            'L' | '<' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Right => Self::Up,
+            Self::Down => Self::Right,
+            Self::Left => Self::Down,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_left().turn_left()
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    pub fn get_offset(self) -> Point {
+        match self {
+            Self::Up => Point::new(0, -1),
+            Self::Right => Point::new(1, 0),
+            Self::Down => Point::new(0, 1),
+            Self::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let point = Point::new(2, -3);
+
+        assert_eq!(point.step(Direction::Up, 4), Point::new(2, -7));
+        assert_eq!(point.step(Direction::Left, 1) - point, Point::new(-1, 0));
+        assert_eq!(point.get_manhattan_distance(Point::new(-1, 1)), 7);
+        assert_eq!(
+            Point::default().get_neighbors(),
+            [
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_direction() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.reverse().reverse(), direction);
+            assert_eq!(
+                direction.get_offset() + direction.reverse().get_offset(),
+                Point::default()
+            );
+        }
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::parse('v'), Some(Direction::Down));
+        assert_eq!(Direction::parse('x'), None);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::error::ParseError;
+use crate::geometry::Direction;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+pub type Coords = (usize, usize);
+
+const ALL_NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+// Cells are stored row by row and addressed as (row, column).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn parse(
+        input: &[String],
+        expected: &str,
+        parse: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let width = input.first().map(|line| line.chars().count()).unwrap_or(0);
+
+        if width == 0 {
+            return Err(ParseError::new(0, 0, &format!("a row of {expected}")));
+        }
+
+        let mut cells = Vec::with_capacity(width * input.len());
+
+        for (row, line) in input.iter().enumerate() {
+            let mut length = 0;
+
+            for (column, c) in line.chars().enumerate() {
+                cells.push(parse(c).ok_or_else(|| ParseError::new(row, column, expected))?);
+                length += 1;
+            }
+
+            if length != width {
+                return Err(ParseError::new(row, length, "rows of the same length"));
+            }
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height: input.len(),
+        })
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, column): Coords) -> Option<&T> {
+        if row < self.height && column < self.width {
+            self.cells.get(row * self.width + column)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (row, column): Coords) -> Option<&mut T> {
+        if row < self.height && column < self.width {
+            self.cells.get_mut(row * self.width + column)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_offset(
+        &self,
+        (row, column): Coords,
+        (rows, columns): (isize, isize),
+    ) -> Option<Coords> {
+        let row = row.checked_add_signed(rows)?;
+        let column = column.checked_add_signed(columns)?;
+
+        (row < self.height && column < self.width).then_some((row, column))
+    }
+
+    pub fn get_next(&self, coords: Coords, direction: Direction) -> Option<Coords> {
+        let offset = direction.get_offset();
+
+        self.get_offset(coords, (offset.y, offset.x))
+    }
+
+    // Up, right, down and left, skipping any that fall off the edge.
+    pub fn get_neighbors(&self, coords: Coords) -> impl Iterator<Item = Coords> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |direction| self.get_next(coords, *direction))
+    }
+
+    // Clockwise from up-left, including the diagonals.
+    pub fn get_all_neighbors(&self, coords: Coords) -> impl Iterator<Item = Coords> + '_ {
+        ALL_NEIGHBORS
+            .iter()
+            .filter_map(move |offset| self.get_offset(coords, *offset))
+    }
+
+    pub fn get_row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn get_rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn get_column(&self, column: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(column).step_by(self.width)
+    }
+
+    pub fn get_columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|column| self.get_column(column))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coords, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| ((i / self.width, i % self.width), cell))
+    }
+
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Coords> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(coords, _)| coords)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn transpose(&self) -> Self {
+        Self {
+            cells: self.get_columns().flatten().cloned().collect(),
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    // Clockwise, so the left column becomes the top row.
+    pub fn rotate(&self) -> Self {
+        Self {
+            cells: self
+                .get_columns()
+                .flat_map(|column| column.collect::<Vec<_>>().into_iter().rev())
+                .cloned()
+                .collect(),
+            width: self.height,
+            height: self.width,
+        }
+    }
+}
+
+impl<T> Index<Coords> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coords: Coords) -> &Self::Output {
+        self.get(coords).expect("should be inside the grid")
+    }
+}
+
+impl<T> IndexMut<Coords> for Grid<T> {
+    fn index_mut(&mut self, coords: Coords) -> &mut Self::Output {
+        self.get_mut(coords).expect("should be inside the grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.get_rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Grid<char> {
+        let input = ["abc".to_string(), "def".to_string()];
+
+        Grid::parse(&input, "a letter", Some).expect("should parse")
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = get_fixture();
+
+        assert_eq!((grid.get_width(), grid.get_height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        let parse_digits = |input: &[&str]| {
+            let input = input.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+            Grid::parse(&input, "a digit", |c| c.to_digit(10))
+        };
+
+        assert_eq!(
+            parse_digits(&["12", "3x"]),
+            Err(ParseError::new(1, 1, "a digit"))
+        );
+        assert_eq!(
+            parse_digits(&["12", "3"]),
+            Err(ParseError::new(1, 1, "rows of the same length"))
+        );
+    }
+
+    #[test]
+    fn test_get_neighbors() {
+        let grid = get_fixture();
+
+        assert_eq!(
+            grid.get_neighbors((0, 1)).collect::<Vec<_>>(),
+            vec![(0, 2), (1, 1), (0, 0)]
+        );
+        assert_eq!(
+            grid.get_all_neighbors((1, 0)).collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = get_fixture();
+
+        assert_eq!(grid.get_row(1), &['d', 'e', 'f']);
+        assert_eq!(
+            grid.get_columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = get_fixture();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+    }
+}
@@ -0,0 +1,80 @@
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod error;
+pub mod geometry;
+pub mod grid;
+pub mod pathfinding;
+pub mod ranges;
+pub mod registry;
+pub mod runner;
+
+use registry::Registry;
+
+macro_rules! days {
+    ($($day:literal => $module:ident [$($part:ident),+] $(render [$($render:ident),+])?),+ $(,)?) => {
+        pub fn get_registry() -> Registry {
+            let mut registry = Registry::default();
+
+            $(
+                registry.register($day, &[$($module::$part),+]);
+                $(registry.register_renders($day, &[$($module::$render),+]);)?
+            )+
+
+            registry
+        }
+    };
+}
+
+days! {
+    1 => day01 [part1, part2],
+    2 => day02 [part1, part2],
+    3 => day03 [part1, part2],
+    4 => day04 [part1, part2],
+    5 => day05 [part1, part2] render [render1, render2],
+    6 => day06 [part1, part2],
+    7 => day07 [part1, part2],
+    8 => day08 [part1, part2],
+    9 => day09 [part1, part2],
+    10 => day10 [part1, part2],
+    11 => day11 [part1, part2],
+    12 => day12 [part1, part2],
+    13 => day13 [part1, part2],
+    14 => day14 [part1, part2],
+    15 => day15 [part1, part2],
+    16 => day16 [part1, part2],
+    17 => day17 [part1, part2] render [render1, render2],
+    18 => day18 [part1, part2],
+    19 => day19 [part1, part2],
+    20 => day20 [part1, part2] render [render, render],
+    21 => day21 [part1, part2],
+    22 => day22 [part1, part2],
+    23 => day23 [part1, part2],
+    24 => day24 [part1, part2],
+    25 => day25 [part1],
+}
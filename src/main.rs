@@ -1,88 +1,215 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day19;
-pub mod day20;
-pub mod day21;
-
+use advent::answers::{Answers, Check};
+use advent::registry::RenderOptions;
+use advent::runner::{self, Outcome, Run};
+use advent::{bench, get_registry};
 use clap::Parser;
-use core::panic;
-use std::io::{self, BufRead};
+use itertools::Itertools;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::process;
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[arg(long, short)]
-    day: u8,
-    #[arg(long, short)]
-    part: u8,
+    #[arg(long, short, required_unless_present_any = ["list", "all"])]
+    day: Option<u8>,
+    #[arg(long, short, required_unless_present_any = ["list", "all"])]
+    part: Option<u8>,
+    #[arg(long, short, help = "List the available days and parts")]
+    list: bool,
+    #[arg(
+        long,
+        short,
+        conflicts_with_all = ["day", "part"],
+        help = "Run every day and part against the files in the inputs directory"
+    )]
+    all: bool,
+    #[arg(
+        long,
+        default_value = "inputs",
+        help = "Directory of dayNN.txt input files"
+    )]
+    inputs: PathBuf,
+    #[arg(
+        long,
+        short,
+        conflicts_with = "all",
+        help = "Read the input from a file instead of stdin"
+    )]
+    input: Option<PathBuf>,
+    #[arg(long, short, help = "Compare answers against the answers file")]
+    check: bool,
+    #[arg(
+        long,
+        default_value = "answers.toml",
+        help = "File of known-correct answers used by --check"
+    )]
+    answers: PathBuf,
+    #[arg(
+        long,
+        short,
+        conflicts_with_all = ["all", "check"],
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Time this many runs, after one warm-up run, and report min, median and max"
+    )]
+    bench: Option<u32>,
+    #[arg(
+        long,
+        requires = "bench",
+        help = "Also write the benchmark results as JSON"
+    )]
+    bench_json: Option<PathBuf>,
+    #[arg(
+        long,
+        short,
+        conflicts_with_all = ["all", "bench"],
+        help = "Draw how the answer was found, for the days that support it"
+    )]
+    render: bool,
+    #[arg(
+        long,
+        requires = "render",
+        help = "Press the button this many times before rendering, for day 20"
+    )]
+    presses: Option<usize>,
 }
 
 fn main() {
     let args = Args::parse();
-    let stdin = io::stdin();
-    let handle = stdin.lock();
-    let input = handle.lines().map_while(Result::ok).collect();
-    let output = match (args.day, args.part) {
-        (1, 1) => day01::part1(input),
-        (1, 2) => day01::part2(input),
-        (2, 1) => day02::part1(input),
-        (2, 2) => day02::part2(input),
-        (3, 1) => day03::part1(input),
-        (3, 2) => day03::part2(input),
-        (4, 1) => day04::part1(input),
-        (4, 2) => day04::part2(input),
-        (5, 1) => day05::part1(input),
-        (5, 2) => day05::part2(input),
-        (6, 1) => day06::part1(input),
-        (6, 2) => day06::part2(input),
-        (7, 1) => day07::part1(input),
-        (7, 2) => day07::part2(input),
-        (8, 1) => day08::part1(input),
-        (8, 2) => day08::part2(input),
-        (9, 1) => day09::part1(input),
-        (9, 2) => day09::part2(input),
-        (10, 1) => day10::part1(input),
-        (10, 2) => day10::part2(input),
-        (11, 1) => day11::part1(input),
-        (11, 2) => day11::part2(input),
-        (12, 1) => day12::part1(input),
-        (12, 2) => day12::part2(input),
-        (13, 1) => day13::part1(input),
-        (13, 2) => day13::part2(input),
-        (14, 1) => day14::part1(input),
-        (14, 2) => day14::part2(input),
-        (15, 1) => day15::part1(input),
-        (15, 2) => day15::part2(input),
-        (16, 1) => day16::part1(input),
-        (16, 2) => day16::part2(input),
-        (17, 1) => day17::part1(input),
-        (17, 2) => day17::part2(input),
-        (18, 1) => day18::part1(input),
-        (18, 2) => day18::part2(input),
-        (19, 1) => day19::part1(input),
-        (19, 2) => day19::part2(input),
-        (20, 1) => day20::part1(input),
-        (20, 2) => day20::part2(input),
-        (21, 1) => day21::part1(input),
-        (21, 2) => day21::part2(input),
-        _ => panic!("Incomplete day or part"),
+    let registry = get_registry();
+
+    if args.list {
+        for day in registry.get_days() {
+            println!(
+                "day {day}: part {}",
+                registry.get_parts(day).iter().join(", ")
+            );
+        }
+
+        return;
+    }
+
+    let answers = args.check.then(|| {
+        Answers::read(&args.answers).unwrap_or_else(|error| {
+            eprintln!("{error}");
+
+            process::exit(2);
+        })
+    });
+
+    if args.all {
+        let runs = runner::run_all(&registry, &args.inputs);
+
+        print!("{}", runner::format_table(&runs, answers.as_ref()));
+
+        if let Some(answers) = answers {
+            if runs
+                .iter()
+                .any(|run| matches!(answers.check(run), Check::Fail { .. }))
+            {
+                process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    let (Some(day), Some(part)) = (args.day, args.part) else {
+        unreachable!("clap should require a day and part");
     };
+    let solver = registry.get(day, part).unwrap_or_else(|error| {
+        eprintln!("{error}");
+
+        process::exit(2);
+    });
+
+    if args.presses.is_some() && day != 20 {
+        eprintln!("--presses only applies to day 20, not day {day}");
+
+        process::exit(2);
+    }
+
+    let input = get_input(&args, day).unwrap_or_else(|error| {
+        eprintln!("{error}");
+
+        process::exit(1);
+    });
+
+    if let Some(runs) = args.bench {
+        let bench = bench::bench(solver, &input, runs as usize).unwrap_or_else(|error| {
+            eprintln!("{}", error.render(&input));
+
+            process::exit(1);
+        });
+
+        println!("{bench}");
+
+        if let Some(path) = &args.bench_json {
+            fs::write(path, bench.to_json() + "\n").unwrap_or_else(|error| {
+                eprintln!("Could not write {}: {error}", path.display());
+
+                process::exit(1);
+            });
+        }
+
+        return;
+    }
+
+    if args.render {
+        let Some(render) = solver.render else {
+            eprintln!("No renderer for day {day} part {part}");
+
+            process::exit(2);
+        };
+        let options = RenderOptions {
+            presses: args.presses,
+        };
+        let rendered = render(input.clone(), &options).unwrap_or_else(|error| {
+            eprintln!("{}", error.render(&input));
+
+            process::exit(1);
+        });
+
+        println!("{rendered}");
+    }
+
+    let output = (solver.solve)(input.clone()).unwrap_or_else(|error| {
+        eprintln!("{}", error.render(&input));
+
+        process::exit(1);
+    });
 
     println!("{output}");
+
+    if let Some(answers) = answers {
+        let check = answers.check(&Run {
+            day,
+            part,
+            outcome: Outcome::Solved(output),
+            elapsed: None,
+        });
+
+        eprintln!("{check}");
+
+        if let Check::Fail { .. } = check {
+            process::exit(1);
+        }
+    }
+}
+
+fn get_input(args: &Args, day: u8) -> Result<Vec<String>, String> {
+    let stdin = io::stdin();
+    let path = match &args.input {
+        Some(path) => path.to_owned(),
+        None if stdin.is_terminal() => runner::get_input_path(&args.inputs, day),
+        None => return Ok(stdin.lock().lines().map_while(Result::ok).collect()),
+    };
+
+    runner::read_input(&path).map_err(|error| {
+        format!(
+            "Could not read {}: {error}. Pass --input <path> or pipe the input through stdin.",
+            path.display()
+        )
+    })
 }
@@ -0,0 +1,120 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: usize,
+    pub nodes: Vec<N>,
+}
+
+// Nodes are numbered as they are discovered, so the heap only has to order numbers and the nodes
+// themselves don't need to be Ord.
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut get_successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut nodes = Vec::<N>::new();
+    let mut indexes = HashMap::<N, usize>::new();
+    let mut costs = Vec::<usize>::new();
+    let mut previous = Vec::<Option<usize>>::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if let Entry::Vacant(entry) = indexes.entry(start.clone()) {
+            entry.insert(nodes.len());
+            heap.push(Reverse((0, nodes.len())));
+            nodes.push(start);
+            costs.push(0);
+            previous.push(None);
+        }
+    }
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        if cost > costs[index] {
+            continue;
+        }
+
+        if is_goal(&nodes[index]) {
+            let mut path = vec![nodes[index].clone()];
+            let mut current = index;
+
+            while let Some(before) = previous[current] {
+                path.push(nodes[before].clone());
+                current = before;
+            }
+
+            path.reverse();
+
+            return Some(Path { cost, nodes: path });
+        }
+
+        for (next, step) in get_successors(&nodes[index]) {
+            let next_cost = cost + step;
+            let next_index = match indexes.entry(next) {
+                Entry::Occupied(entry) => {
+                    let next_index = *entry.get();
+
+                    if next_cost >= costs[next_index] {
+                        continue;
+                    }
+
+                    costs[next_index] = next_cost;
+                    previous[next_index] = Some(index);
+
+                    next_index
+                }
+                Entry::Vacant(entry) => {
+                    let next_index = nodes.len();
+
+                    nodes.push(entry.key().clone());
+                    entry.insert(next_index);
+                    costs.push(next_cost);
+                    previous.push(Some(index));
+
+                    next_index
+                }
+            };
+
+            heap.push(Reverse((next_cost, next_index)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_successors(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 7), ('c', 2)],
+            'b' => vec![('d', 1)],
+            'c' => vec![('b', 3), ('d', 8)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        assert_eq!(
+            dijkstra(['a'], get_successors, |node| *node == 'd'),
+            Some(Path {
+                cost: 6,
+                nodes: vec!['a', 'c', 'b', 'd'],
+            })
+        );
+        assert_eq!(
+            dijkstra(['b', 'c'], get_successors, |node| *node == 'd').map(|path| path.cost),
+            Some(1)
+        );
+        assert_eq!(dijkstra(['d'], get_successors, |node| *node == 'a'), None);
+    }
+}
@@ -0,0 +1,161 @@
+use num::PrimInt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry<T> {
+    source: Range<T>,
+    destination: T,
+}
+
+// Maps values in each source range onto a destination range of the same length, and leaves any
+// value outside every source range as it is. Ranges are half-open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    entries: Vec<Entry<T>>,
+}
+
+impl<T: PrimInt> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.source.start < source.start);
+
+        self.entries.insert(
+            index,
+            Entry {
+                source,
+                destination,
+            },
+        );
+    }
+
+    pub fn get(&self, value: T) -> T {
+        self.entries
+            .iter()
+            .find(|entry| entry.source.contains(&value))
+            .map(|entry| entry.destination + (value - entry.source.start))
+            .unwrap_or(value)
+    }
+
+    // Splits each range wherever it crosses the edge of a source range, and maps every piece.
+    pub fn get_ranges(&self, ranges: &[Range<T>]) -> Vec<Range<T>> {
+        let mut mapped = vec![];
+
+        for range in ranges {
+            let mut start = range.start;
+
+            for entry in &self.entries {
+                if start >= range.end {
+                    break;
+                }
+
+                if entry.source.end <= start || entry.source.start >= range.end {
+                    continue;
+                }
+
+                if start < entry.source.start {
+                    mapped.push(start..entry.source.start);
+                    start = entry.source.start;
+                }
+
+                let end = range.end.min(entry.source.end);
+                let destination = entry.destination + (start - entry.source.start);
+
+                mapped.push(destination..destination + (end - start));
+                start = end;
+            }
+
+            if start < range.end {
+                mapped.push(start..range.end);
+            }
+        }
+
+        mapped
+    }
+
+    // Every value that maps onto the given one, in ascending order. A value only maps onto itself
+    // when it is outside every source range, so there may be several preimages or none.
+    pub fn get_preimages(&self, value: T) -> Vec<T> {
+        let mut preimages = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                entry.destination <= value
+                    && value - entry.destination < entry.source.end - entry.source.start
+            })
+            .map(|entry| entry.source.start + (value - entry.destination))
+            .collect::<Vec<_>>();
+
+        if !self
+            .entries
+            .iter()
+            .any(|entry| entry.source.contains(&value))
+        {
+            preimages.push(value);
+        }
+
+        preimages.sort();
+
+        preimages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> RangeMap<u32> {
+        let mut map = RangeMap::default();
+
+        map.insert(98..100, 50);
+        map.insert(50..98, 52);
+
+        map
+    }
+
+    #[test]
+    fn test_get() {
+        let map = get_fixture();
+
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(98), 50);
+        assert_eq!(map.get(100), 100);
+        assert_eq!(map.get(10), 10);
+    }
+
+    #[test]
+    fn test_get_ranges() {
+        let map = get_fixture();
+
+        assert_eq!(
+            map.get_ranges(&[40..60, 95..105]),
+            vec![40..50, 52..62, 97..100, 50..52, 100..105]
+        );
+        assert_eq!(map.get_ranges(&[0..10, 60..60]), vec![0..10]);
+    }
+
+    #[test]
+    fn test_get_preimages() {
+        let map = get_fixture();
+
+        assert_eq!(map.get_preimages(81), vec![79]);
+        assert_eq!(map.get_preimages(50), vec![98]);
+        assert_eq!(map.get_preimages(10), vec![10]);
+        assert_eq!(map.get_preimages(99), vec![97]);
+        assert_eq!(map.get_preimages(51), vec![99]);
+        assert_eq!(map.get_preimages(100), vec![100]);
+
+        let mut map = RangeMap::default();
+
+        map.insert(7..8, 5);
+
+        assert_eq!(map.get_preimages(5), vec![5, 7]);
+        assert_eq!(map.get_preimages(7), vec![]);
+    }
+}
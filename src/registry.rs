@@ -0,0 +1,190 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use itertools::Itertools;
+use std::fmt;
+
+pub type Solve = fn(Vec<String>) -> Result<Answer, SolveError>;
+pub type Render = fn(Vec<String>, &RenderOptions) -> Result<String, SolveError>;
+
+// Command line settings for renderers, each of which ignores the ones it has no use for.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub presses: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    pub solve: Solve,
+    pub render: Option<Render>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    UnknownDay {
+        day: u8,
+        available: Vec<u8>,
+    },
+    UnknownPart {
+        day: u8,
+        part: u8,
+        available: Vec<u8>,
+    },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownDay { day, available } => write!(
+                f,
+                "No solver for day {day}. Available days: {}",
+                available.iter().join(", ")
+            ),
+            Self::UnknownPart {
+                day,
+                part,
+                available,
+            } => write!(
+                f,
+                "No solver for day {day} part {part}. Available parts for day {day}: {}",
+                available.iter().join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    solvers: Vec<Solver>,
+}
+
+impl Registry {
+    pub fn register(&mut self, day: u8, parts: &[Solve]) {
+        for (part, solve) in (1..).zip(parts) {
+            self.solvers.push(Solver {
+                day,
+                part,
+                solve: *solve,
+                render: None,
+            });
+        }
+
+        self.solvers.sort_by_key(|solver| (solver.day, solver.part));
+    }
+
+    // Renderers are matched up with the parts already registered for the day, in order.
+    pub fn register_renders(&mut self, day: u8, renders: &[Render]) {
+        for (part, render) in (1..).zip(renders) {
+            if let Some(solver) = self
+                .solvers
+                .iter_mut()
+                .find(|solver| solver.day == day && solver.part == part)
+            {
+                solver.render = Some(*render);
+            }
+        }
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Result<&Solver, RegistryError> {
+        if let Some(solver) = self
+            .solvers
+            .iter()
+            .find(|solver| solver.day == day && solver.part == part)
+        {
+            return Ok(solver);
+        }
+
+        let parts = self.get_parts(day);
+
+        if parts.is_empty() {
+            Err(RegistryError::UnknownDay {
+                day,
+                available: self.get_days(),
+            })
+        } else {
+            Err(RegistryError::UnknownPart {
+                day,
+                part,
+                available: parts,
+            })
+        }
+    }
+
+    pub fn get_days(&self) -> Vec<u8> {
+        self.solvers
+            .iter()
+            .map(|solver| solver.day)
+            .dedup()
+            .collect()
+    }
+
+    pub fn get_parts(&self, day: u8) -> Vec<u8> {
+        self.solvers
+            .iter()
+            .filter(|solver| solver.day == day)
+            .map(|solver| solver.part)
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Solver> {
+        self.solvers.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_fixture() -> Registry {
+        let mut registry = Registry::default();
+
+        registry.register(2, &[|_| Ok(21.into()), |_| Ok(22.into())]);
+        registry.register(1, &[|input| Ok(input.len().into())]);
+        registry.register_renders(2, &[|_, _| Ok("21".to_string())]);
+
+        registry
+    }
+
+    #[test]
+    fn test_get() {
+        let registry = get_fixture();
+        let solver = registry.get(1, 1).expect("should have a solver");
+
+        assert_eq!(
+            (solver.solve)(vec!["a".to_string(), "b".to_string()]),
+            Ok(2.into())
+        );
+        assert_eq!(
+            (registry.get(2, 2).expect("should have a solver").solve)(vec![]),
+            Ok(22.into())
+        );
+        assert_eq!(
+            registry
+                .get(2, 1)
+                .ok()
+                .and_then(|solver| solver.render)
+                .map(|render| render(vec![], &RenderOptions::default())),
+            Some(Ok("21".to_string()))
+        );
+        assert!(solver.render.is_none());
+        assert_eq!(registry.get_days(), vec![1, 2]);
+        assert_eq!(registry.get_parts(2), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_get_unknown() {
+        let registry = get_fixture();
+
+        assert_eq!(
+            registry.get(3, 1).unwrap_err().to_string(),
+            "No solver for day 3. Available days: 1, 2"
+        );
+        assert_eq!(
+            registry.get(1, 2).unwrap_err().to_string(),
+            "No solver for day 1 part 2. Available parts for day 1: 1"
+        );
+    }
+}
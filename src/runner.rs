@@ -0,0 +1,226 @@
+use crate::answer::Answer;
+use crate::answers::Answers;
+use crate::error::SolveError;
+use crate::registry::{Registry, Solver};
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(Answer),
+    Failed(SolveError),
+    MissingInput(PathBuf),
+    Panicked,
+}
+
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub elapsed: Option<Duration>,
+}
+
+pub fn get_input_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day:02}.txt"))
+}
+
+pub fn read_input(path: &Path) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+pub fn run(solver: &Solver, input: Vec<String>) -> Run {
+    let start = Instant::now();
+    let result = panic::catch_unwind(|| (solver.solve)(input));
+    let elapsed = start.elapsed();
+
+    Run {
+        day: solver.day,
+        part: solver.part,
+        outcome: match result {
+            Ok(Ok(answer)) => Outcome::Solved(answer),
+            Ok(Err(error)) => Outcome::Failed(error),
+            Err(_) => Outcome::Panicked,
+        },
+        elapsed: Some(elapsed),
+    }
+}
+
+pub fn run_all(registry: &Registry, dir: &Path) -> Vec<Run> {
+    let solvers = registry.iter().collect::<Vec<_>>();
+
+    solvers
+        .par_iter()
+        .map(|solver| {
+            let path = get_input_path(dir, solver.day);
+
+            match read_input(&path) {
+                Ok(input) => run(solver, input),
+                Err(_) => Run {
+                    day: solver.day,
+                    part: solver.part,
+                    outcome: Outcome::MissingInput(path),
+                    elapsed: None,
+                },
+            }
+        })
+        .collect()
+}
+
+pub fn format_table(runs: &[Run], answers: Option<&Answers>) -> String {
+    let mut header = vec!["Day", "Part", "Answer", "Time"];
+
+    if answers.is_some() {
+        header.push("Check");
+    }
+
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let mut row = vec![
+                run.day.to_string(),
+                run.part.to_string(),
+                match &run.outcome {
+                    Outcome::Solved(answer) => answer.to_string(),
+                    Outcome::Failed(error) => error.to_string(),
+                    Outcome::MissingInput(path) => format!("missing {}", path.display()),
+                    Outcome::Panicked => "panicked".to_string(),
+                },
+                run.elapsed
+                    .map(|elapsed| format!("{elapsed:.2?}"))
+                    .unwrap_or_else(|| "-".to_string()),
+            ];
+
+            if let Some(answers) = answers {
+                row.push(answers.check(run).to_string());
+            }
+
+            row
+        })
+        .collect::<Vec<_>>();
+    let header = header.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let mut widths = header.iter().map(|s| s.chars().count()).collect::<Vec<_>>();
+
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+
+    for row in [header].iter().chain(&rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| match column {
+                0 | 1 | 3 => format!("{cell:>width$}"),
+                _ => format!("{cell:<width$}"),
+            })
+            .join("  ");
+
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_input_path() {
+        assert_eq!(
+            get_input_path(Path::new("inputs"), 5),
+            PathBuf::from("inputs/day05.txt")
+        );
+    }
+
+    #[test]
+    fn test_run_all() {
+        let dir = std::env::temp_dir().join(format!("advent-runner-{}", std::process::id()));
+        let mut registry = Registry::default();
+
+        fs::create_dir_all(&dir).expect("should create a temporary directory");
+        fs::write(dir.join("day01.txt"), "a\nb\nc\n").expect("should write an input");
+
+        registry.register(
+            1,
+            &[
+                |input| Ok(input.len().into()),
+                |_| panic!("should be caught"),
+                |_| Err(SolveError::Unsolvable("no answer".to_string())),
+            ],
+        );
+        registry.register(2, &[|_| Ok(0.into())]);
+
+        let runs = run_all(&registry, &dir);
+
+        fs::remove_dir_all(&dir).expect("should remove the temporary directory");
+
+        assert_eq!(
+            runs.iter()
+                .map(|run| run.outcome.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                Outcome::Solved(3.into()),
+                Outcome::Panicked,
+                Outcome::Failed(SolveError::Unsolvable("no answer".to_string())),
+                Outcome::MissingInput(dir.join("day02.txt")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_table() {
+        let runs = vec![
+            Run {
+                day: 1,
+                part: 1,
+                outcome: Outcome::Solved(142.into()),
+                elapsed: Some(Duration::from_micros(1500)),
+            },
+            Run {
+                day: 10,
+                part: 2,
+                outcome: Outcome::Panicked,
+                elapsed: None,
+            },
+        ];
+
+        assert_eq!(
+            format_table(&runs, None),
+            [
+                "Day  Part  Answer      Time",
+                "  1     1  142       1.50ms",
+                " 10     2  panicked       -",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            format_table(
+                &runs,
+                Some(&Answers::parse("[day01]\npart1 = 142").expect("should parse"))
+            ),
+            [
+                "Day  Part  Answer      Time  Check",
+                "  1     1  142       1.50ms  pass",
+                " 10     2  panicked       -  no recorded answer",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}